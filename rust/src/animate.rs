@@ -0,0 +1,142 @@
+//! Drawing simulations frame by frame in the terminal, clearing the screen
+//! for each frame with ANSI escape codes.
+
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+use crate::grid::{Direction, Grid, Pos};
+
+const HIDE_CURSOR: &str = "\x1b[?25l";
+const SHOW_CURSOR: &str = "\x1b[?25h";
+/// Clear the whole screen and move the cursor to the top left, so that a
+/// frame taller than the terminal can't leave parts of the last one behind.
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+pub struct Animation<W: Write> {
+    out: W,
+    frame_time: Duration,
+    next_frame: Option<Instant>,
+    /// Whether a frame has been drawn, and the cursor hidden.
+    drawn: bool,
+}
+
+impl Animation<std::io::Stdout> {
+    pub fn stdout(fps: u32) -> Self {
+        Animation::new(std::io::stdout(), fps)
+    }
+}
+
+impl<W: Write> Animation<W> {
+    /// Draw at most `fps` frames a second, or as fast as possible if it's 0.
+    pub fn new(out: W, fps: u32) -> Self {
+        let frame_time = match fps {
+            0 => Duration::from_secs(0),
+            fps => Duration::from_secs(1) / fps,
+        };
+        Animation {
+            out,
+            frame_time,
+            next_frame: None,
+            drawn: false,
+        }
+    }
+
+    /// Replace the last frame with `frame`, first waiting until it's due.
+    pub fn frame(&mut self, frame: &str) -> std::io::Result<()> {
+        if let Some(next_frame) = self.next_frame {
+            let now = Instant::now();
+            if next_frame > now {
+                std::thread::sleep(next_frame - now);
+            }
+        }
+        if !self.drawn {
+            write!(self.out, "{}", HIDE_CURSOR)?;
+        }
+        write!(self.out, "{}{}", CLEAR_SCREEN, frame)?;
+        if !frame.ends_with('\n') {
+            writeln!(self.out)?;
+        }
+        self.out.flush()?;
+        self.drawn = true;
+        self.next_frame = Some(Instant::now() + self.frame_time);
+        Ok(())
+    }
+}
+
+impl<W: Write> Drop for Animation<W> {
+    fn drop(&mut self) {
+        if self.drawn {
+            let _ = write!(self.out, "{}", SHOW_CURSOR);
+            let _ = self.out.flush();
+        }
+    }
+}
+
+/// The glyph for an actor facing `dir`.
+pub fn arrow(dir: Direction) -> char {
+    match dir {
+        Direction::North => '^',
+        Direction::East => '>',
+        Direction::South => 'v',
+        Direction::West => '<',
+    }
+}
+
+/// Draw a grid with `actors` drawn over it, leaving empty cells blank. The
+/// frame covers the grid and the actors.
+pub fn render<T>(grid: &Grid<T>, actors: &[(Pos, char)]) -> String
+where
+    T: std::fmt::Display,
+{
+    let positions = || grid.0.keys().chain(actors.iter().map(|(p, _)| p));
+    let (min_x, max_x) = match (
+        positions().map(|p| p.x).min(),
+        positions().map(|p| p.x).max(),
+    ) {
+        (Some(min), Some(max)) => (min, max),
+        _ => return String::new(),
+    };
+    let min_y = positions().map(|p| p.y).min().unwrap();
+    let max_y = positions().map(|p| p.y).max().unwrap();
+
+    let mut frame = String::new();
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            let p = Pos::new(x, y);
+            match (actors.iter().rev().find(|(a, _)| *a == p), grid.0.get(&p)) {
+                (Some((_, glyph)), _) => frame.push(*glyph),
+                (None, Some(v)) => frame.push_str(&v.to_string()),
+                (None, None) => frame.push(' '),
+            }
+        }
+        frame.push('\n');
+    }
+    frame
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let grid: Grid<char> = "#.\n.#\n".parse().unwrap();
+        let robot = (Pos::new(2, 0), arrow(Direction::East));
+        assert_eq!(render(&grid, &[robot]), "# >\n # \n");
+        assert_eq!(render(&Grid::<char>::new(), &[]), "");
+    }
+
+    #[test]
+    fn test_frames() {
+        let mut out = vec![];
+        {
+            let mut animation = Animation::new(&mut out, 0);
+            animation.frame("ab\ncd\n").unwrap();
+            animation.frame("ef").unwrap();
+        }
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\x1b[?25l\x1b[2J\x1b[Hab\ncd\n\x1b[2J\x1b[Hef\n\x1b[?25h"
+        );
+    }
+}
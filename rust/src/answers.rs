@@ -0,0 +1,252 @@
+//! Expected answers for each input file, stored in a small TOML-like format:
+//!
+//! ```text
+//! # comment
+//! [day_01]
+//! 1 = 3266288
+//! 2 = 4896582
+//!
+//! [day_08]
+//! 2 = """
+//! █    ███
+//! """
+//! ```
+//!
+//! Each section is named after the stem of an input file (`day_07.example1`
+//! for `day_07.example1.txt`).
+
+use std::collections::BTreeMap;
+
+use crate::solution::{self, Answer};
+
+const MULTI_LINE: &str = "\"\"\"";
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Answers(pub BTreeMap<String, BTreeMap<u8, Answer>>);
+
+impl Answers {
+    pub fn get(&self, input: &str, part: u8) -> Option<&Answer> {
+        self.0.get(input).and_then(|parts| parts.get(&part))
+    }
+
+    pub fn insert(&mut self, input: &str, part: u8, answer: Answer) {
+        self.0
+            .entry(input.to_string())
+            .or_default()
+            .insert(part, normalize(&answer));
+    }
+}
+
+/// Drop trailing whitespace from each line and trailing blank lines, so that
+/// rendered images compare equal however an editor has saved them.
+pub fn normalize(answer: &str) -> String {
+    let lines: Vec<&str> = answer.lines().map(|l| l.trim_end()).collect();
+    lines.join("\n").trim_end_matches('\n').to_string()
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub msg: String,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.msg)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl std::str::FromStr for Answers {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut answers = Answers::default();
+        let mut section: Option<String> = None;
+        let mut lines = s.lines().enumerate().map(|(i, l)| (i + 1, l));
+
+        while let Some((n, line)) = lines.next() {
+            let err = |msg: &str| ParseError {
+                line: n,
+                msg: msg.to_string(),
+            };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if line.starts_with('[') {
+                let name = line
+                    .strip_prefix('[')
+                    .and_then(|l| l.strip_suffix(']'))
+                    .ok_or_else(|| err("expected [input]"))?;
+                section = Some(name.trim().to_string());
+                continue;
+            }
+
+            let input = section.as_ref().ok_or_else(|| err("answer outside a section"))?;
+            let eq = line.find('=').ok_or_else(|| err("expected PART = ANSWER"))?;
+            let part: u8 = line[..eq]
+                .trim()
+                .parse()
+                .map_err(|_| err("expected a part number"))?;
+            let value = line[eq + 1..].trim();
+            let answer = if value == MULTI_LINE {
+                let mut answer = vec![];
+                loop {
+                    match lines.next() {
+                        Some((_, l)) if l.trim() == MULTI_LINE => break,
+                        Some((_, l)) => answer.push(l),
+                        None => return Err(err("unterminated multi-line answer")),
+                    }
+                }
+                answer.join("\n")
+            } else {
+                value.to_string()
+            };
+            answers.insert(input, part, answer);
+        }
+        Ok(answers)
+    }
+}
+
+impl std::fmt::Display for Answers {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for (i, (input, parts)) in self.0.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            writeln!(f, "[{}]", input)?;
+            for (part, answer) in parts.iter() {
+                if answer.contains('\n') {
+                    writeln!(f, "{} = {}\n{}\n{}", part, MULTI_LINE, answer, MULTI_LINE)?;
+                } else {
+                    writeln!(f, "{} = {}", part, answer)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The result of checking one part against the expected answer.
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+    Pass,
+    Fail { expected: Answer, actual: Answer },
+    Missing { actual: Answer },
+    /// The part hasn't been solved, and has no expected answer yet.
+    Unsolved,
+    Error(String),
+}
+
+impl Outcome {
+    pub fn new(expected: Option<&Answer>, actual: Result<Answer, String>) -> Self {
+        match (expected, actual) {
+            (None, Err(e)) if solution::Error::is_unsolved(&e) => Outcome::Unsolved,
+            (_, Err(e)) => Outcome::Error(e),
+            (None, Ok(actual)) => Outcome::Missing { actual },
+            (Some(expected), Ok(actual)) => {
+                if *expected == normalize(&actual) {
+                    Outcome::Pass
+                } else {
+                    Outcome::Fail {
+                        expected: expected.clone(),
+                        actual: normalize(&actual),
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Outcome::Pass => write!(f, "pass"),
+            Outcome::Missing { actual } if actual.contains('\n') => {
+                write!(f, "missing, got:\n{}", actual)
+            }
+            Outcome::Missing { actual } => write!(f, "missing, got {}", actual),
+            Outcome::Unsolved => write!(f, "unsolved"),
+            Outcome::Error(e) => write!(f, "error: {}", e),
+            Outcome::Fail { expected, actual } => {
+                write!(f, "FAIL")?;
+                let expected: Vec<&str> = expected.lines().collect();
+                let actual: Vec<&str> = actual.lines().collect();
+                for i in 0..std::cmp::max(expected.len(), actual.len()) {
+                    match (expected.get(i), actual.get(i)) {
+                        (Some(e), Some(a)) if e == a => write!(f, "\n    {}", e)?,
+                        (e, a) => {
+                            if let Some(e) = e {
+                                write!(f, "\n  - {}", e)?;
+                            }
+                            if let Some(a) = a {
+                                write!(f, "\n  + {}", a)?;
+                            }
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANSWERS: &str = "\
+# expected answers
+[day_01]
+1 = 3266288
+2 = 4896582
+
+[day_08]
+2 = \"\"\"
+#..#
+.##.
+\"\"\"
+";
+
+    #[test]
+    fn test_parse() {
+        let answers: Answers = ANSWERS.parse().unwrap();
+        assert_eq!(answers.get("day_01", 1), Some(&"3266288".to_string()));
+        assert_eq!(answers.get("day_01", 3), None);
+        assert_eq!(answers.get("day_08", 2), Some(&"#..#\n.##.".to_string()));
+        assert_eq!(answers.to_string().parse(), Ok(answers));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = "1 = 2".parse::<Answers>().unwrap_err();
+        assert_eq!(err.line, 1);
+        let err = "[day_08]\n2 = \"\"\"\n#..#\n".parse::<Answers>().unwrap_err();
+        assert_eq!(err.line, 2);
+    }
+
+    #[test]
+    fn test_outcome() {
+        let expected = "#..#\n.##.".to_string();
+        assert_eq!(
+            Outcome::new(Some(&expected), Ok("#..# \n.##.\n".to_string())),
+            Outcome::Pass
+        );
+        let fail = Outcome::new(Some(&expected), Ok("#..#\n.#..\n".to_string()));
+        assert_eq!(fail.to_string(), "FAIL\n    #..#\n  - .##.\n  + .#..");
+        assert_eq!(
+            Outcome::new(None, Ok("1".to_string())),
+            Outcome::Missing {
+                actual: "1".to_string()
+            }
+        );
+        let unsolved = solution::Error::Unsolved.to_string();
+        assert_eq!(Outcome::new(None, Err(unsolved.clone())), Outcome::Unsolved);
+        assert_eq!(
+            Outcome::new(Some(&expected), Err(unsolved.clone())),
+            Outcome::Error(unsolved)
+        );
+    }
+}
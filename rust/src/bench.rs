@@ -0,0 +1,348 @@
+//! Timing repeated runs of each day, of the Intcode VM and of day 10 on large
+//! generated asteroid fields, and comparing the timings against a saved
+//! baseline.
+//!
+//! A baseline is stored one benchmark per line, with times in nanoseconds:
+//!
+//! ```text
+//! # name runs min median mean
+//! day_01/parse 10 41250 42042 43510
+//! ```
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use crate::day_10;
+use crate::input::{self, DataDirs};
+use crate::intcode;
+use crate::report::format_duration;
+use crate::solution::{Day, PARTS};
+
+/// An Intcode program to benchmark.
+pub struct Program {
+    /// The input holding the program.
+    pub input: &'static str,
+    /// Memory to set before running.
+    pub set: &'static [(usize, i64)],
+    /// Values to give the program, repeated as needed.
+    pub values: &'static [i64],
+}
+
+pub const PROGRAMS: &[Program] = &[
+    Program {
+        input: "day_02",
+        set: &[(1, 12), (2, 2)],
+        values: &[],
+    },
+    Program {
+        input: "day_05",
+        set: &[],
+        values: &[5],
+    },
+    Program {
+        input: "day_09",
+        set: &[],
+        values: &[2],
+    },
+];
+
+/// The sizes of the square asteroid fields to time day 10's visibility
+/// counts on, as the puzzle inputs are too small to show much.
+pub const FIELDS: &[i32] = &[50, 100];
+
+/// How likely each cell of a generated field is to have an asteroid.
+const FIELD_DENSITY: f64 = 0.3;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stats {
+    pub runs: u32,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+}
+
+impl Stats {
+    /// Summarise the times of at least one run.
+    pub fn new(mut times: Vec<Duration>) -> Self {
+        times.sort();
+        let runs = times.len() as u32;
+        let mid = times.len() / 2;
+        let median = if times.len().is_multiple_of(2) {
+            (times[mid - 1] + times[mid]) / 2
+        } else {
+            times[mid]
+        };
+        Stats {
+            runs,
+            min: times[0],
+            median,
+            mean: times.iter().sum::<Duration>() / runs,
+        }
+    }
+}
+
+/// Named timings, e.g. `day_07/parse` or `intcode/day_09`.
+#[derive(Debug, Default, PartialEq)]
+pub struct Results(pub BTreeMap<String, Stats>);
+
+impl Results {
+    /// Print a table of the results, with the change in median time from
+    /// `baseline` where it has the same benchmark.
+    pub fn compare(&self, baseline: Option<&Results>) -> String {
+        let width = self.0.keys().map(|n| n.len()).max().unwrap_or(0);
+        let width = std::cmp::max(width, "name".len());
+        let mut table = format!(
+            "{:<width$} {:>5} {:>12} {:>12} {:>12}",
+            "name",
+            "runs",
+            "min",
+            "median",
+            "mean",
+            width = width
+        );
+        if baseline.is_some() {
+            table.push_str(&format!(" {:>12} {:>8}", "baseline", "change"));
+        }
+        for (name, stats) in self.0.iter() {
+            table.push_str(&format!(
+                "\n{:<width$} {:>5} {:>12} {:>12} {:>12}",
+                name,
+                stats.runs,
+                format_duration(stats.min),
+                format_duration(stats.median),
+                format_duration(stats.mean),
+                width = width
+            ));
+            if let Some(base) = baseline.and_then(|b| b.0.get(name)) {
+                let change = match base.median.as_nanos() {
+                    // There's no change to measure from nothing.
+                    0 => "n/a".to_string(),
+                    _ => format!(
+                        "{:+.1}%",
+                        (stats.median.as_secs_f64() / base.median.as_secs_f64() - 1.) * 100.
+                    ),
+                };
+                table.push_str(&format!(
+                    " {:>12} {:>8}",
+                    format_duration(base.median),
+                    change
+                ));
+            }
+        }
+        table.push('\n');
+        table
+    }
+}
+
+impl std::fmt::Display for Results {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "# name runs min median mean")?;
+        for (name, stats) in self.0.iter() {
+            writeln!(
+                f,
+                "{} {} {} {} {}",
+                name,
+                stats.runs,
+                stats.min.as_nanos(),
+                stats.median.as_nanos(),
+                stats.mean.as_nanos()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for Results {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut results = Results::default();
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let err = || format!("line {}: expected NAME RUNS MIN MEDIAN MEAN", i + 1);
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() != 5 {
+                return Err(err());
+            }
+            let nanos = |s: &str| s.parse().map(Duration::from_nanos).map_err(|_| err());
+            let stats = Stats {
+                runs: fields[1].parse().map_err(|_| err())?,
+                min: nanos(fields[2])?,
+                median: nanos(fields[3])?,
+                mean: nanos(fields[4])?,
+            };
+            results.0.insert(fields[0].to_string(), stats);
+        }
+        Ok(results)
+    }
+}
+
+/// Time `runs` runs of one day on `data`: parsing, and solving each of
+/// `parts`. Parse times are taken from the runs of the first part. Results
+/// are named after `input`, e.g. `day_07.example1/parse`, so that timings on
+/// different inputs aren't compared.
+pub fn day(
+    solution: &dyn Day,
+    input: &str,
+    parts: &[u8],
+    data: &str,
+    runs: u32,
+) -> Result<Results, String> {
+    // Names can't have spaces in them, as a baseline is split on whitespace.
+    let name: String = input
+        .chars()
+        .map(|c| if c.is_whitespace() { '_' } else { c })
+        .collect();
+    let mut results = Results::default();
+    for (i, &part) in parts.iter().enumerate() {
+        let mut parse_times = vec![];
+        let mut solve_times = vec![];
+        for _ in 0..runs {
+            let run = solution.run(part, data).map_err(|e| e.to_string())?;
+            parse_times.push(run.parse_time);
+            solve_times.push(run.solve_time);
+        }
+        if i == 0 {
+            results
+                .0
+                .insert(format!("{}/parse", name), Stats::new(parse_times));
+        }
+        results
+            .0
+            .insert(format!("{}/part_{}", name, part), Stats::new(solve_times));
+    }
+    Ok(results)
+}
+
+impl Program {
+    /// Time `runs` runs of `intcode::Program::run` on `memory`.
+    pub fn time(&self, memory: &[i64], runs: u32) -> Stats {
+        let times = (0..runs)
+            .map(|_| {
+                let mut program = intcode::Program::new(memory.to_vec());
+                for &(addr, val) in self.set {
+                    program.memory[addr] = val;
+                }
+                let start = Instant::now();
+                program.run(&mut self.values.iter().copied().cycle());
+                start.elapsed()
+            })
+            .collect();
+        Stats::new(times)
+    }
+}
+
+/// Time `runs` runs of counting the asteroids visible from each asteroid in
+/// generated fields of each of `sizes`.
+pub fn fields(sizes: &[i32], runs: u32) -> Results {
+    let mut results = Results::default();
+    for &size in sizes {
+        let field = day_10::generate_field(size, size, FIELD_DENSITY, size as u64);
+        let times = (0..runs)
+            .map(|_| {
+                let start = Instant::now();
+                field.to_counts();
+                start.elapsed()
+            })
+            .collect();
+        results
+            .0
+            .insert(format!("day_10/field_{}", size), Stats::new(times));
+    }
+    results
+}
+
+/// Time every day on its default input, every program in `PROGRAMS`, and
+/// day 10 on every field in `FIELDS`.
+pub fn all(dirs: &DataDirs, days: &[&dyn Day], runs: u32) -> Result<Results, String> {
+    let mut results = Results::default();
+    for solution in days {
+        let name = input::name(solution.day(), None);
+        let data = dirs.read(&name).map_err(|e| e.to_string())?;
+        results
+            .0
+            .extend(day(*solution, &name, PARTS, &data, runs)?.0);
+    }
+    for program in PROGRAMS {
+        let data = dirs.read(program.input).map_err(|e| e.to_string())?;
+        let memory = intcode::parse(&data).map_err(|e| format!("{}: {}", program.input, e))?;
+        let stats = program.time(&memory, runs);
+        results
+            .0
+            .insert(format!("intcode/{}", program.input), stats);
+    }
+    results.0.extend(fields(FIELDS, runs).0);
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::new(vec![ms(4), ms(1), ms(9), ms(2)]);
+        assert_eq!(
+            stats,
+            Stats {
+                runs: 4,
+                min: ms(1),
+                median: ms(3),
+                mean: ms(4)
+            }
+        );
+        assert_eq!(Stats::new(vec![ms(3), ms(1), ms(2)]).median, ms(2));
+    }
+
+    #[test]
+    fn test_compare() {
+        let stats = |median| Stats {
+            runs: 3,
+            min: ms(1),
+            median: ms(median),
+            mean: ms(2),
+        };
+        let mut baseline = Results::default();
+        baseline.0.insert("day_01/parse".to_string(), stats(2));
+        let mut results = Results::default();
+        results.0.insert("day_01/parse".to_string(), stats(3));
+        results.0.insert("intcode/day_09".to_string(), stats(1));
+
+        assert_eq!(
+            results.compare(Some(&baseline)),
+            "\
+name            runs          min       median         mean     baseline   change
+day_01/parse       3      1.000ms      3.000ms      2.000ms      2.000ms   +50.0%
+intcode/day_09     3      1.000ms      1.000ms      2.000ms
+"
+        );
+        assert_eq!(results.to_string().parse(), Ok(results));
+
+        let mut zero = Results::default();
+        zero.0.insert("day_01/parse".to_string(), stats(0));
+        assert!(zero.compare(Some(&zero)).ends_with("     n/a\n"));
+    }
+
+    #[test]
+    fn test_day_names() {
+        let solution = crate::solution::find(1).unwrap();
+        let results = day(solution, "day_01.my input", &[2], "12\n", 1).unwrap();
+        let names: Vec<&String> = results.0.keys().collect();
+        assert_eq!(names, ["day_01.my_input/parse", "day_01.my_input/part_2"]);
+    }
+
+    #[test]
+    fn test_fields() {
+        let results = fields(&[10, 20], 2);
+        let names: Vec<&String> = results.0.keys().collect();
+        assert_eq!(names, ["day_10/field_10", "day_10/field_20"]);
+        assert!(results.0.values().all(|stats| stats.runs == 2));
+    }
+}
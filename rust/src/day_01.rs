@@ -1,19 +1,25 @@
-pub fn solve(part: u8, data: Result<String, std::io::Error>) {
-    let masses: Vec<i32> = data
-        .expect("couldn't read data file")
-        .lines()
-        .map(|s| s.parse::<i32>().unwrap())
-        .collect();
-    match part {
-        1 => {
-            let result1: i32 = masses.iter().map(|&m| part_1::fuel(m)).sum();
-            println!("{}", result1);
-        }
-        2 => {
-            let result2: i32 = masses.iter().map(|&m| part_2::fuel(m)).sum();
-            println!("{}", result2);
-        }
-        _ => (),
+use crate::solution::{Answer, Error, Solution};
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    const DAY: u8 = 1;
+
+    type Input = Vec<i32>;
+
+    fn parse(data: &str) -> Result<Self::Input, Error> {
+        let masses = data.lines().map(|s| s.parse::<i32>()).collect::<Result<_, _>>()?;
+        Ok(masses)
+    }
+
+    fn part_1(masses: &Self::Input) -> Result<Answer, Error> {
+        let result: i32 = masses.iter().map(|&m| part_1::fuel(m)).sum();
+        Ok(result.to_string())
+    }
+
+    fn part_2(masses: &Self::Input) -> Result<Answer, Error> {
+        let result: i32 = masses.iter().map(|&m| part_2::fuel(m)).sum();
+        Ok(result.to_string())
     }
 }
 
@@ -1,37 +1,39 @@
 use std::iter::repeat;
-use crate::intcode::Program;
+use crate::intcode::{self, Program};
+use crate::solution::{Answer, Error, Solution};
 
-pub fn solve(part: u8, data: Result<String, std::io::Error>) {
-    let memory: Vec<i64> = data
-        .expect("couldn't read data file")
-        .split(",")
-        .map(|s| s.parse::<i64>().expect("bad data"))
-        .collect();
+pub struct Day02;
 
-    match part {
-        1 => {
-            let mut program = Program::new(memory);
-            program.memory[1] = 12;
-            program.memory[2] = 2;
-            let _output = program.run(&mut repeat(0));
-            println!("{}", program.memory[0]);
-        }
-        2 => {
-            let result = part_2::force(&memory);
-            println!("{}", result);
-        }
-        _ => (),
+impl Solution for Day02 {
+    const DAY: u8 = 2;
+
+    type Input = Vec<i64>;
+
+    fn parse(data: &str) -> Result<Self::Input, Error> {
+        Ok(intcode::parse(data)?)
+    }
+
+    fn part_1(memory: &Self::Input) -> Result<Answer, Error> {
+        let mut program = Program::new(memory.clone());
+        program.memory[1] = 12;
+        program.memory[2] = 2;
+        let _output = program.run(&mut repeat(0));
+        Ok(program.memory[0].to_string())
+    }
+
+    fn part_2(memory: &Self::Input) -> Result<Answer, Error> {
+        Ok(part_2::force(memory).to_string())
     }
 }
 
 pub mod part_2 {
     use std::iter::repeat;
     use crate::intcode::Program;
-    pub fn force(memory: &Vec<i64>) -> i64 {
+    pub fn force(memory: &[i64]) -> i64 {
         let mut noun = 0;
         let mut verb = 0;
         loop {
-            let mut p = Program::new(memory.clone());
+            let mut p = Program::new(memory.to_vec());
             p.memory[1] = noun;
             p.memory[2] = verb;
             let _output = p.run(&mut repeat(0));
@@ -39,9 +41,9 @@ pub mod part_2 {
                 break noun * 100 + verb;
             }
             if verb < 99 {
-                verb = verb + 1
+                verb += 1
             } else {
-                noun = noun + 1;
+                noun += 1;
                 verb = 0
             };
         }
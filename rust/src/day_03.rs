@@ -1,67 +1,51 @@
-pub fn solve(part: u8, data: Result<String, std::io::Error>) {
-    use part_1::*;
-    let data: String = data.expect("couldn't read data file");
-    let mut lines = data.lines();
-    let moves_1 = lines.next().expect("wrong number of lines in data");
-    let moves_1 = parse_moves(&moves_1).expect("couldn't parse moves from data");
-    let moves_2 = lines.next().expect("wrong number of lines in data");
-    let moves_2 = parse_moves(&moves_2).expect("couldn't parse moves from data");
-    match part {
-        1 => {
-            let result = part_1::solve(&moves_1, &moves_2)
-                .map_or(String::from("No solution"), |i| format!("{}", i));
-            println!("{}", result)
-        }
-        2 => {
-            let result = part_2::solve(&moves_1, &moves_2)
-                .map_or(String::from("No solution"), |i| format!("{}", i));
-            println!("{}", result)
-        }
-        _ => (),
-    }
-}
+use crate::solution::{Answer, Error, Solution};
 
-mod part_1 {
-    use std::str::FromStr;
+pub struct Day03;
 
-    #[derive(Debug)]
-    pub struct ParseError;
+impl Solution for Day03 {
+    const DAY: u8 = 3;
 
-    #[derive(Clone, Copy)]
-    enum Dir {
-        U,
-        R,
-        D,
-        L,
-    }
+    type Input = (Vec<part_1::Move>, Vec<part_1::Move>);
 
-    impl Dir {
-        fn is_vertical(&self) -> bool {
-            use Dir::*;
-            match self {
-                U | D => true,
-                L | R => false,
-            }
-        }
+    fn parse(data: &str) -> Result<Self::Input, Error> {
+        let mut lines = data.lines();
+        let mut next_moves = || {
+            let line = lines
+                .next()
+                .ok_or_else(|| Error::Parse("wrong number of lines in data".to_string()))?;
+            part_1::parse_moves(line)
+                .map_err(|_| Error::Parse(format!("couldn't parse moves: {}", line)))
+        };
+        let moves_1 = next_moves()?;
+        let moves_2 = next_moves()?;
+        Ok((moves_1, moves_2))
     }
 
-    impl FromStr for Dir {
-        type Err = ParseError;
+    fn part_1((moves_1, moves_2): &Self::Input) -> Result<Answer, Error> {
+        part_1::solve(moves_1, moves_2)
+            .map(|i| i.to_string())
+            .ok_or_else(|| Error::NoAnswer("the wires don't cross".to_string()))
+    }
 
-        fn from_str(s: &str) -> Result<Self, Self::Err> {
-            match s {
-                "U" => Ok(Self::U),
-                "R" => Ok(Self::R),
-                "D" => Ok(Self::D),
-                "L" => Ok(Self::L),
-                _ => Err(ParseError),
-            }
-        }
+    fn part_2((moves_1, moves_2): &Self::Input) -> Result<Answer, Error> {
+        part_2::solve(moves_1, moves_2)
+            .map(|i| i.to_string())
+            .ok_or_else(|| Error::NoAnswer("the wires don't cross".to_string()))
     }
+}
+
+mod part_1 {
+    use std::str::FromStr;
+
+    pub use crate::grid::Pos;
+    use crate::grid::Direction;
+
+    #[derive(Debug)]
+    pub struct ParseError;
 
     #[derive(Clone, Copy)]
     pub struct Move {
-        dir: Dir,
+        dir: Direction,
         steps: u32,
     }
 
@@ -70,7 +54,7 @@ mod part_1 {
 
         fn from_str(s: &str) -> Result<Self, Self::Err> {
             let (dir, steps) = s.split_at(1);
-            let dir = dir.parse::<Dir>();
+            let dir = dir.parse::<Direction>().map_err(|_| ParseError);
             dir.and_then(|dir| {
                 let steps = steps.parse::<u32>().map_err(|_| ParseError);
                 steps.map(|steps| Move { dir, steps })
@@ -82,34 +66,10 @@ mod part_1 {
         s.split(",").map(|s| s.parse::<Move>()).collect()
     }
 
-    #[derive(Clone, Copy, PartialEq)]
-    pub struct Pos {
-        x: i32,
-        y: i32,
-    }
-
-    impl Pos {
-        fn new(x: i32, y: i32) -> Self {
-            Self { x, y }
-        }
-
-        pub const CENTRAL_PORT: Self = Self { x: 0, y: 0 };
+    pub const CENTRAL_PORT: Pos = Pos { x: 0, y: 0 };
 
-        fn manhattan_dist(&self, other: &Self) -> u32 {
-            (other.x - self.x).abs() as u32 + (other.y - self.y).abs() as u32
-        }
-
-        fn move_(&self, Move { dir, steps }: Move) -> Self {
-            let Pos { x, y } = *self;
-            let steps = steps as i32;
-            use Dir::*;
-            match dir {
-                U => Self::new(x, y - steps),
-                R => Self::new(x + steps, y),
-                D => Self::new(x, y + steps),
-                L => Self::new(x - steps, y),
-            }
-        }
+    fn end(start: Pos, Move { dir, steps }: Move) -> Pos {
+        start.step(dir, steps as i32)
     }
 
     #[derive(Clone, Copy)]
@@ -122,7 +82,7 @@ mod part_1 {
     type Path = Vec<Segment>;
 
     pub fn path(moves: &[Move]) -> Path {
-        let init = (Pos::CENTRAL_PORT, 0);
+        let init = (CENTRAL_PORT, 0);
         let mut path = Vec::new();
         moves.iter().fold(init, |(pos, steps), &move_| {
             let segment = Segment {
@@ -131,7 +91,7 @@ mod part_1 {
                 steps_to_start: steps,
             };
             path.push(segment);
-            let pos = pos.move_(move_);
+            let pos = end(pos, move_);
             let steps = steps + move_.steps;
 
             (pos, steps)
@@ -156,7 +116,7 @@ mod part_1 {
     impl Segment {
         fn ends(&self) -> (Pos, Pos) {
             let Pos { x: x1, y: y1 } = self.start;
-            let Pos { x: x2, y: y2 } = self.start.move_(self.move_);
+            let Pos { x: x2, y: y2 } = end(self.start, self.move_);
             (
                 Pos {
                     x: std::cmp::min(x1, x2),
@@ -173,8 +133,8 @@ mod part_1 {
             let (Pos { x: hx1, y: hy }, Pos { x: hx2, y: _ }) = h.ends();
             let (Pos { x: vx, y: vy1 }, Pos { x: _, y: vy2 }) = v.ends();
             if (hx1 <= vx && vx <= hx2) && (vy1 <= hy && hy <= vy2) {
-                let h_steps: u32 = h.steps_to_start + (vx - h.start.x).abs() as u32;
-                let v_steps: u32 = v.steps_to_start + (hy - v.start.y).abs() as u32;
+                let h_steps: u32 = h.steps_to_start + (vx - h.start.x).unsigned_abs();
+                let v_steps: u32 = v.steps_to_start + (hy - v.start.y).unsigned_abs();
                 Some(Intersection {
                     pos: Pos { x: vx, y: hy },
                     steps: h_steps + v_steps,
@@ -187,8 +147,8 @@ mod part_1 {
         fn intersection(&self, other: &Self) -> Option<Intersection> {
             match (self.move_.dir.is_vertical(), other.move_.dir.is_vertical()) {
                 (true, true) | (false, false) => None,
-                (true, false) => Self::intersection_perpendicular(&other, self),
-                (false, true) => Self::intersection_perpendicular(self, &other),
+                (true, false) => Self::intersection_perpendicular(other, self),
+                (false, true) => Self::intersection_perpendicular(self, other),
             }
         }
     }
@@ -206,13 +166,13 @@ mod part_1 {
 
     fn closest_to<'a>(is: &'a [Intersection], &pos: &Pos) -> Option<&'a Intersection> {
         let mut is: Vec<&Intersection> = is.iter().filter(|i| i.pos != pos).collect();
-        is.sort_by_cached_key(|i| i.pos.manhattan_dist(&pos));
-        is.first().map(|&i| i)
+        is.sort_by_cached_key(|i| i.pos.dist_to(&pos));
+        is.first().copied()
     }
 
     pub fn solve(moves1: &[Move], moves2: &[Move]) -> Option<u32> {
         let is = intersections(&path(moves1), &path(moves2));
-        closest_to(&is, &Pos::CENTRAL_PORT).map(|i| i.pos.manhattan_dist(&Pos::CENTRAL_PORT))
+        closest_to(&is, &CENTRAL_PORT).map(|i| i.pos.dist_to(&CENTRAL_PORT) as u32)
     }
 
     #[cfg(test)]
@@ -239,11 +199,11 @@ mod part_1 {
 mod part_2 {
     use super::part_1::*;
 
-    fn least_steps<'a>(is: &'a [Intersection]) -> Option<&'a Intersection> {
+    fn least_steps(is: &[Intersection]) -> Option<&Intersection> {
         let mut is: Vec<&Intersection> =
-            is.iter().filter(|i| i.pos() != Pos::CENTRAL_PORT).collect();
+            is.iter().filter(|i| i.pos() != CENTRAL_PORT).collect();
         is.sort_by_key(|i| i.steps());
-        is.first().map(|&i| i)
+        is.first().copied()
     }
 
     pub fn solve(moves1: &[Move], moves2: &[Move]) -> Option<u32> {
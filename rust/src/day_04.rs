@@ -1,16 +1,35 @@
-pub fn solve(part: u8, _data: Result<String, std::io::Error>) {
-    let range = 265275..781584;
-    match part {
-        1 => {
-            let result = range.filter(|&i| part_1::meets_criteria(i)).count();
-            println!("{}", result);
-        }
-        2 => {
-            let result = range.filter(|&i| part_2::meets_criteria(i)).count();
-            println!("{}", result);
+use std::ops::RangeInclusive;
+
+use crate::solution::{Answer, Error, Solution};
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    const DAY: u8 = 4;
+
+    type Input = RangeInclusive<i32>;
+
+    fn parse(data: &str) -> Result<Self::Input, Error> {
+        let bounds = data
+            .trim()
+            .split('-')
+            .map(|s| s.parse::<i32>())
+            .collect::<Result<Vec<i32>, _>>()?;
+        match bounds[..] {
+            [lo, hi] => Ok(lo..=hi),
+            _ => Err(Error::Parse(format!("expected a range, got {}", data))),
         }
-        _ => (),
-    };
+    }
+
+    fn part_1(range: &Self::Input) -> Result<Answer, Error> {
+        let result = range.clone().filter(|&i| part_1::meets_criteria(i)).count();
+        Ok(result.to_string())
+    }
+
+    fn part_2(range: &Self::Input) -> Result<Answer, Error> {
+        let result = range.clone().filter(|&i| part_2::meets_criteria(i)).count();
+        Ok(result.to_string())
+    }
 }
 
 mod part_1 {
@@ -69,7 +88,7 @@ mod part_2 {
                 group = 1;
             }
         }
-        return group == 2;
+        group == 2
     }
 
     pub fn meets_criteria(i: i32) -> bool {
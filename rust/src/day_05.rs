@@ -1,24 +1,34 @@
 use std::iter::repeat;
-use crate::intcode::Program;
+use crate::intcode::{self, Program};
+use crate::solution::{Answer, Error, Solution};
 
-pub fn solve(part: u8, data: Result<String, std::io::Error>) {
-    let memory: Vec<i64> = data
-        .expect("couldn't read data file")
-        .split(",")
-        .map(|s| s.parse::<i64>().expect("bad data"))
-        .collect();
+pub struct Day05;
 
-    let mut program = Program::new(memory);
+impl Solution for Day05 {
+    const DAY: u8 = 5;
 
-    match part {
-        1 => {
-            let output = program.run(&mut repeat(1));
-            println!("{:?}", output);
-        }
-        2 => {
-            let output = program.run(&mut repeat(5));
-            println!("{:?}", output);
-        }
-        _ => (),
+    type Input = Vec<i64>;
+
+    fn parse(data: &str) -> Result<Self::Input, Error> {
+        Ok(intcode::parse(data)?)
+    }
+
+    fn part_1(memory: &Self::Input) -> Result<Answer, Error> {
+        diagnostic_code(memory, 1)
+    }
+
+    fn part_2(memory: &Self::Input) -> Result<Answer, Error> {
+        diagnostic_code(memory, 5)
+    }
+}
+
+/// Run the diagnostic program for the given system ID. Every output before
+/// the diagnostic code is a test result, which is zero if the test passed.
+fn diagnostic_code(memory: &[i64], system_id: i64) -> Result<Answer, Error> {
+    let mut program = Program::new(memory.to_vec());
+    let output = program.run(&mut repeat(system_id));
+    match output.split_last() {
+        Some((code, tests)) if tests.iter().all(|&t| t == 0) => Ok(code.to_string()),
+        _ => Err(Error::NoAnswer(format!("diagnostics failed: {:?}", output))),
     }
 }
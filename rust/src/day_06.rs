@@ -1,12 +1,24 @@
-pub fn solve(part: u8, data: Result<String, std::io::Error>) {
-    let t: part_1::OrbitTree = data
-        .expect("Couldn't read data file")
-        .parse()
-        .expect("Couldn't parse data");
-    match part {
-        1 => println!("{}", t.checksum()),
-        2 => println!("{}", t.transfers_required("YOU", "SAN").unwrap()),
-        _ => (),
+use crate::solution::{Answer, Error, Solution};
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    const DAY: u8 = 6;
+
+    type Input = part_1::OrbitTree;
+
+    fn parse(data: &str) -> Result<Self::Input, Error> {
+        data.parse().map_err(Error::Parse)
+    }
+
+    fn part_1(t: &Self::Input) -> Result<Answer, Error> {
+        Ok(t.checksum().to_string())
+    }
+
+    fn part_2(t: &Self::Input) -> Result<Answer, Error> {
+        t.transfers_required("YOU", "SAN")
+            .map(|n| n.to_string())
+            .ok_or_else(|| Error::NoAnswer("YOU and SAN aren't both in orbit".to_string()))
     }
 }
 
@@ -36,12 +48,11 @@ mod part_1 {
                 Some(self)
             } else {
                 for o in self.orbitees.iter_mut() {
-                    match o.find(object) {
-                        Some(t) => return Some(t),
-                        None => (),
+                    if let Some(t) = o.find(object) {
+                        return Some(t);
                     }
                 }
-                return None;
+                None
             }
         }
 
@@ -59,13 +70,7 @@ mod part_1 {
         }
 
         fn fmt_rec(&self, f: &mut fmt::Formatter, depth: u32) -> fmt::Result {
-            write!(
-                f,
-                "{:width$}{}\n",
-                "",
-                self.object,
-                width = (depth as usize)
-            )?;
+            writeln!(f, "{:width$}{}", "", self.object, width = (depth as usize))?;
             for o in self.orbitees.iter() {
                 o.fmt_rec(f, depth + 1)?;
             }
@@ -131,8 +136,7 @@ mod part_1 {
             let h = leaf("H");
             let g = new("G", vec![h]);
             let b = new("B", vec![c, g]);
-            let com = new("COM", vec![b]);
-            com
+            new("COM", vec![b])
         }
 
         #[test]
@@ -198,7 +202,7 @@ mod part_2 {
                 self.orbitees
                     .iter()
                     .find_map(|o| o.first_common_ancestor(a, b))
-                    .or(Some(&self))
+                    .or(Some(self))
             } else {
                 None
             }
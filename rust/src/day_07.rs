@@ -1,23 +1,28 @@
-use crate::intcode::{Program, State};
-
-pub fn solve(part: u8, data: Result<String, std::io::Error>) {
-    let memory: Vec<i64> = data
-        .expect("couldn't read data file")
-        .split(",")
-        .map(|s| s.parse::<i64>().expect("bad data"))
-        .collect();
-
-    match part {
-        1 => {
-            let program = Program::new(memory.clone());
-            let output = part_1::find_largest_thruster_signal(&program);
-            println!("{:?}", output);
-        }
-        2 => {
-            let output = part_2::find_largest_thruster_signal(&memory);
-            println!("{:?}", output);
-        }
-        _ => (),
+use crate::intcode::{self, Program, State};
+use crate::solution::{Answer, Error, Solution};
+
+pub struct Day07;
+
+impl Solution for Day07 {
+    const DAY: u8 = 7;
+
+    type Input = Vec<i64>;
+
+    fn parse(data: &str) -> Result<Self::Input, Error> {
+        Ok(intcode::parse(data)?)
+    }
+
+    fn part_1(memory: &Self::Input) -> Result<Answer, Error> {
+        let program = Program::new(memory.clone());
+        part_1::find_largest_thruster_signal(&program)
+            .map(|(_, signal)| signal.to_string())
+            .ok_or_else(|| Error::NoAnswer("no phase settings".to_string()))
+    }
+
+    fn part_2(memory: &Self::Input) -> Result<Answer, Error> {
+        part_2::find_largest_thruster_signal(memory)
+            .map(|(_, signal)| signal.to_string())
+            .ok_or_else(|| Error::NoAnswer("no phase settings".to_string()))
     }
 }
 
@@ -60,7 +65,7 @@ mod part_1 {
         inputs.push_back(phase_setting);
         inputs.push_back(input);
         let mut program = program.clone();
-        program.run(&mut inputs.iter().cloned()).first().map(|x| *x)
+        program.run(&mut inputs.iter().cloned()).first().copied()
     }
 
     fn thruster_signal(program: &Program, p: &PhaseSetting) -> i64 {
@@ -68,8 +73,8 @@ mod part_1 {
         let b_output = run_amp(program, p.b, a_output).expect("amp B produced no output");
         let c_output = run_amp(program, p.c, b_output).expect("amp C produced no output");
         let d_output = run_amp(program, p.d, c_output).expect("amp D produced no output");
-        let e_output = run_amp(program, p.e, d_output).expect("amp E produced no output");
-        e_output
+
+        run_amp(program, p.e, d_output).expect("amp E produced no output")
     }
 
     pub fn find_largest_thruster_signal(program: &Program) -> Option<(PhaseSetting, i64)> {
@@ -167,37 +172,34 @@ mod part_2 {
             0,
         ]);
 
-        loop {
-            match amps.pop_front() {
-                Some(mut amp) => {
-                    let mut had_input = false;
-                    loop {
-                        match amp.program.state {
-                            State::Terminated => {
-                                break;
-                            }
-                            State::Running => amp.program.step(),
-                            State::Output(o) => {
-                                inputs.push_back(o);
-                                amp.program.state = State::Running;
-                                amps.push_back(amp);
-                                break;
-                            }
-                            State::WaitForInput(addr) => {
-                                if had_input {
-                                    amps.push_back(amp);
-                                    break;
-                                } else {
-                                    had_input = true;
-                                    let input = inputs.pop_front().expect("not enough input");
-                                    amp.program.memory[addr] = input;
-                                    amp.program.state = State::Running;
-                                }
-                            }
+        while let Some(mut amp) = amps.pop_front() {
+            let mut had_input = false;
+            loop {
+                match amp.program.state {
+                    State::Terminated => {
+                        break;
+                    }
+                    State::Running => amp.program.step(),
+                    State::Output(o) => {
+                        inputs.push_back(o);
+                        amp.program.state = State::Running;
+                        amps.push_back(amp);
+                        break;
+                    }
+                    State::WaitForInput(addr) => {
+                        if had_input {
+                            amps.push_back(amp);
+                            break;
+                        } else {
+                            had_input = true;
+                            let input = inputs
+                                .pop_front()
+                                .unwrap_or_else(|| panic!("not enough input for amp {}", amp.name));
+                            amp.program.memory[addr] = input;
+                            amp.program.state = State::Running;
                         }
                     }
                 }
-                None => break,
             }
         }
 
@@ -1,62 +1,185 @@
-pub fn solve(part: u8, data: Result<String, std::io::Error>) {
-    let s = part_1::Size::new(25, 6);
-    let i = part_1::Image::from_str(data.expect("Could not read data file").as_str(), s);
-    match part {
-        1 => println!("{}", i.checksum().unwrap()),
-        2 => println!("{}", i),
-        _ => (),
+use crate::solution::{Answer, Error, Solution};
+
+pub use part_1::{Image, ImageError, LayerStats, Size, SIZE};
+
+pub struct Day08;
+
+impl Solution for Day08 {
+    const DAY: u8 = 8;
+
+    type Input = part_1::Image;
+
+    fn parse(data: &str) -> Result<Self::Input, Error> {
+        part_1::Image::from_str(data, SIZE).map_err(|e| Error::Parse(e.to_string()))
+    }
+
+    fn part_1(i: &Self::Input) -> Result<Answer, Error> {
+        i.checksum()
+            .map(|c| c.to_string())
+            .ok_or_else(|| Error::NoAnswer("the image has no layers".to_string()))
+    }
+
+    fn part_2(i: &Self::Input) -> Result<Answer, Error> {
+        i.render().map_err(|e| Error::NoAnswer(e.to_string()))?;
+        // Fall back to the image itself if it can't be read.
+        Ok(i.letters().unwrap_or_else(|_| i.to_string()))
     }
 }
 
 mod part_1 {
 
+    #[derive(Clone, Copy, Debug, PartialEq)]
     pub struct Size {
         pub width: usize,
         pub height: usize,
     }
 
+    /// The size of the puzzle's images.
+    pub const SIZE: Size = Size {
+        width: 25,
+        height: 6,
+    };
+
     impl Size {
         pub fn new(width: usize, height: usize) -> Self {
             Self { width, height }
         }
 
-        pub fn size(&self) -> usize {
-            self.width * self.height
+        /// The number of pixels in a layer of this size.
+        pub fn size(&self) -> Result<usize, ImageError> {
+            match self.width.checked_mul(self.height) {
+                Some(0) => Err(ImageError::ZeroSize),
+                Some(size) => Ok(size),
+                None => Err(ImageError::TooLarge {
+                    width: self.width,
+                    height: self.height,
+                }),
+            }
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub enum ImageError {
+        /// The image has no width or no height.
+        ZeroSize,
+        /// A layer of this size has more pixels than fit in a `usize`.
+        TooLarge { width: usize, height: usize },
+        /// There are no pixels, so not even one layer.
+        Empty,
+        /// The character at this index isn't a digit.
+        NotADigit { index: usize, found: char },
+        /// The pixels don't fill a whole number of layers of this many
+        /// pixels.
+        SizeMismatch { pixels: usize, layer: usize },
+        /// The pixel at this position is transparent in every layer.
+        Transparent { x: usize, y: usize },
+    }
+
+    impl std::fmt::Display for ImageError {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            match self {
+                ImageError::ZeroSize => write!(f, "the image must be at least 1x1"),
+                ImageError::TooLarge { width, height } => {
+                    write!(f, "a {}x{} image is too large", width, height)
+                }
+                ImageError::Empty => write!(f, "the image has no pixels"),
+                ImageError::NotADigit { index, found } => {
+                    write!(f, "expected a digit at {}, got {:?}", index, found)
+                }
+                ImageError::SizeMismatch { pixels, layer } => write!(
+                    f,
+                    "{} pixels don't make whole layers of {} pixels",
+                    pixels, layer
+                ),
+                ImageError::Transparent { x, y } => {
+                    write!(f, "the pixel at {},{} is transparent in every layer", x, y)
+                }
+            }
         }
     }
 
-    struct Layer<'a>(&'a [u8]);
+    impl std::error::Error for ImageError {}
+
+    /// How many pixels of each digit a layer has.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct LayerStats(pub [usize; 10]);
 
-    impl<'a> Layer<'a> {
-        fn count(&self, px: u8) -> usize {
-            self.0.iter().filter(|&&pix| pix == px).count()
+    impl LayerStats {
+        fn new(layer: &[u8]) -> Self {
+            let mut counts = [0; 10];
+            for &px in layer {
+                counts[px as usize] += 1;
+            }
+            LayerStats(counts)
+        }
+
+        /// How many pixels are `px`, or `None` if `px` isn't a digit.
+        pub fn count(&self, px: u8) -> Option<usize> {
+            self.0.get(px as usize).copied()
         }
     }
 
     pub struct Image {
-        pub size: Size,
+        size: Size,
+        /// The number of pixels in each layer, checked against `size`.
+        layer_len: usize,
         pub pixels: Vec<u8>,
     }
 
     impl Image {
-        pub fn from_str(s: &str, size: Size) -> Image {
-            let pixels: Vec<u8> = s.as_bytes().iter().map(|b| b - 48).collect();
-            Image { pixels, size }
+        /// Read an image of whole layers of `size`, ignoring whitespace around
+        /// it.
+        pub fn from_str(s: &str, size: Size) -> Result<Image, ImageError> {
+            let layer_len = size.size()?;
+            let pixels = s
+                .trim()
+                .chars()
+                .enumerate()
+                .map(|(index, c)| {
+                    c.to_digit(10)
+                        .map(|d| d as u8)
+                        .ok_or(ImageError::NotADigit { index, found: c })
+                })
+                .collect::<Result<Vec<u8>, _>>()?;
+            if pixels.is_empty() {
+                return Err(ImageError::Empty);
+            }
+            if !pixels.len().is_multiple_of(layer_len) {
+                return Err(ImageError::SizeMismatch {
+                    pixels: pixels.len(),
+                    layer: layer_len,
+                });
+            }
+            Ok(Image {
+                size,
+                layer_len,
+                pixels,
+            })
+        }
+
+        pub fn size(&self) -> Size {
+            self.size
         }
 
-        fn layers(&self) -> impl Iterator<Item = Layer> {
-            self.pixels
-                .chunks(self.size.width * self.size.height)
-                .map(|pxs| Layer(pxs))
+        /// The number of pixels in each layer.
+        pub fn layer_len(&self) -> usize {
+            self.layer_len
         }
 
-        fn layer_with_fewest_zeros(&self) -> Option<Layer> {
-            self.layers().min_by_key(|l| l.count(0))
+        pub fn layers(&self) -> impl Iterator<Item = &[u8]> {
+            self.pixels.chunks(self.layer_len)
+        }
+
+        /// The count of each digit in each layer, from the top layer down.
+        pub fn layer_stats(&self) -> Vec<LayerStats> {
+            self.layers().map(LayerStats::new).collect()
         }
 
         pub fn checksum(&self) -> Option<usize> {
-            self.layer_with_fewest_zeros()
-                .map(|l| l.count(1) * l.count(2))
+            self.layer_stats()
+                .into_iter()
+                .min_by_key(|l| l.0[0])
+                .map(|l| l.0[1] * l.0[2])
         }
     }
 
@@ -66,45 +189,144 @@ mod part_1 {
 
         #[test]
         fn test_1() {
-            let i = Image::from_str("123456789012", Size::new(3, 2));
+            let i = Image::from_str("123456789012", Size::new(3, 2)).unwrap();
             assert_eq!(i.pixels[0], 1);
+            assert_eq!(i.checksum(), Some(1));
+        }
+
+        #[test]
+        fn test_errors() {
+            let from_str = |s, w, h| Image::from_str(s, Size::new(w, h)).err();
+            assert_eq!(from_str("123456789012\n", 3, 2), None);
+            assert_eq!(
+                from_str("1234a6", 3, 2),
+                Some(ImageError::NotADigit {
+                    index: 4,
+                    found: 'a'
+                })
+            );
+            assert_eq!(
+                from_str("12345678", 3, 2),
+                Some(ImageError::SizeMismatch {
+                    pixels: 8,
+                    layer: 6
+                })
+            );
+            assert_eq!(from_str("", 0, 6), Some(ImageError::ZeroSize));
+            assert_eq!(from_str("", 3, 2), Some(ImageError::Empty));
+            assert_eq!(from_str(" \n", 3, 2), Some(ImageError::Empty));
+            // Would wrap around to 0 if multiplied unchecked.
+            let half = 1 << (usize::BITS / 2);
+            assert_eq!(
+                from_str("", half, half),
+                Some(ImageError::TooLarge {
+                    width: half,
+                    height: half
+                })
+            );
+        }
+
+        #[test]
+        fn test_layer_stats() {
+            let i = Image::from_str("001222012345", Size::new(3, 2)).unwrap();
+            let stats = i.layer_stats();
+            assert_eq!(stats.len(), 2);
+            assert_eq!(
+                (stats[0].count(0), stats[0].count(1), stats[0].count(2)),
+                (Some(2), Some(1), Some(3))
+            );
+            assert_eq!(stats[1].count(5), Some(1));
+            assert_eq!(stats[1].count(10), None);
+            assert_eq!(stats[1].0.iter().sum::<usize>(), 6);
         }
     }
 }
 
 mod part_2 {
     use super::part_1::*;
+    use crate::export::{self, Rgb};
+    use crate::ocr;
 
     impl Image {
-        pub fn render(&self) -> Vec<u8> {
-            let size = self.size.size();
-            let mut result = Vec::new();
-            for i in 0..size {
-                let mut layer = 0;
-                loop {
-                    let px = self.pixels[i + layer*size];
-                    if px == 2 {
-                        layer += 1;
-                    } else {
-                        result.push(px);
-                        break
-                    }
-                }
-            }
+        /// Each pixel's topmost colour that isn't transparent, or `None` if
+        /// it's transparent in every layer.
+        fn composite(&self) -> Vec<Option<u8>> {
+            (0..self.layer_len())
+                .map(|i| self.layers().map(|layer| layer[i]).find(|&px| px != 2))
+                .collect()
+        }
 
-            result
+        pub fn render(&self) -> Result<Vec<u8>, ImageError> {
+            self.composite()
+                .into_iter()
+                .enumerate()
+                .map(|(i, px)| {
+                    px.ok_or(ImageError::Transparent {
+                        x: i % self.size().width,
+                        y: i / self.size().width,
+                    })
+                })
+                .collect()
+        }
+
+        /// The letters drawn in the image.
+        pub fn letters(&self) -> Result<String, ocr::Error> {
+            let pixels: Vec<Vec<bool>> = self.rendered_rows(|px| px == Some(1));
+            ocr::recognize(&pixels)
+        }
+
+        fn rendered_rows<P>(&self, pixel: impl Fn(Option<u8>) -> P) -> Vec<Vec<P>> {
+            self.composite()
+                .chunks(self.size().width)
+                .map(|row| row.iter().map(|&px| pixel(px)).collect())
+                .collect()
+        }
+
+        /// The image as a PGM, black and white, with pixels that are
+        /// transparent all the way through in grey.
+        pub fn to_pgm(&self) -> String {
+            export::pgm(&self.rendered_rows(|px| match px {
+                Some(1) => 255,
+                None => 128,
+                _ => 0,
+            }))
+        }
+
+        /// The image as a PPM, coloured like the PGM.
+        pub fn to_ppm(&self) -> String {
+            export::ppm(&self.rendered_rows(|px| match px {
+                Some(1) => Rgb::WHITE,
+                None => Rgb(128, 128, 128),
+                _ => Rgb::BLACK,
+            }))
+        }
+
+        /// The image as an SVG, with each pixel `scale` units wide.
+        pub fn to_svg(&self, scale: u32) -> String {
+            export::svg(
+                &self.rendered_rows(|px| match px {
+                    Some(0) => Some(Rgb::BLACK),
+                    Some(1) => Some(Rgb::WHITE),
+                    _ => None,
+                }),
+                scale,
+            )
         }
     }
 
+    /// Draws pixels that are transparent all the way through as `?`.
     impl std::fmt::Display for Image {
         fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            let pixels = self.render();
-            let lines = pixels.chunks(self.size.width);
-            for line in lines {
+            for line in self.rendered_rows(|px| px) {
                 for px in line {
-                    write!(f, "{}", if *px == 0 { " " } else { "█" })?;
+                    let c = match px {
+                        Some(0) => " ",
+                        Some(_) => "█",
+                        None => "?",
+                    };
+                    write!(f, "{}", c)?;
                 }
-                writeln!(f, "")?;
+                writeln!(f)?;
             }
             Ok(())
         }
@@ -116,8 +338,28 @@ mod part_2 {
 
         #[test]
         fn test_1() {
-            let i = Image::from_str("0222112222120000", Size::new(2, 2));
-            assert_eq!(i.render(), vec![0, 1, 1, 0]);
+            let i = Image::from_str("0222112222120000", Size::new(2, 2)).unwrap();
+            assert_eq!(i.render(), Ok(vec![0, 1, 1, 0]));
+        }
+
+        #[test]
+        fn test_transparent() {
+            let i = Image::from_str("0222122222120002", Size::new(2, 2)).unwrap();
+            assert_eq!(i.render(), Err(ImageError::Transparent { x: 1, y: 1 }));
+            assert_eq!(i.to_string(), "  \n█?\n");
+            assert_eq!(i.to_pgm(), "P2\n2 2\n255\n0 0\n255 128\n");
+            assert!(i.to_ppm().ends_with("255 255 255 128 128 128\n"));
+        }
+
+        #[test]
+        fn test_export() {
+            let i = Image::from_str("0222112222120000", Size::new(2, 2)).unwrap();
+            assert_eq!(i.to_pgm(), "P2\n2 2\n255\n0 255\n255 0\n");
+            assert_eq!(
+                i.to_ppm(),
+                "P3\n2 2\n255\n0 0 0 255 255 255\n255 255 255 0 0 0\n"
+            );
+            assert_eq!(i.to_svg(1).matches(r##"fill="#ffffff""##).count(), 2);
         }
     }
 }
@@ -1,27 +1,33 @@
-use crate::intcode::Program;
-
-pub fn solve(part: u8, data: Result<String, std::io::Error>) {
-    let memory: Vec<i64> = data
-        .expect("couldn't read data file")
-        .split(",")
-        .map(|s| s.parse::<i64>().expect("bad data"))
-        .collect();
-
-    let mut p = Program::new(memory);
-
-    match part {
-        1 => {
-            let r = run_with_input(&mut p, 1);
-            println!("{:?}", r);
-        }
-        2 => {
-            let r = run_with_input(&mut p, 2);
-            println!("{:?}", r);
-        }
-        _ => (),
+use crate::intcode::{self, Program};
+use crate::solution::{Answer, Error, Solution};
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    const DAY: u8 = 9;
+
+    type Input = Vec<i64>;
+
+    fn parse(data: &str) -> Result<Self::Input, Error> {
+        Ok(intcode::parse(data)?)
+    }
+
+    fn part_1(memory: &Self::Input) -> Result<Answer, Error> {
+        boost_keycode(memory, 1)
+    }
+
+    fn part_2(memory: &Self::Input) -> Result<Answer, Error> {
+        boost_keycode(memory, 2)
     }
 }
 
+fn boost_keycode(memory: &[i64], input: i64) -> Result<Answer, Error> {
+    let mut p = Program::new(memory.to_vec());
+    run_with_input(&mut p, input)
+        .map(|o| o.to_string())
+        .map_err(|outputs| Error::NoAnswer(format!("malfunctioning opcodes: {:?}", outputs)))
+}
+
 fn run_with_input(p: &mut Program, input: i64) -> Result<i64, Vec<i64>> {
     let mut input = std::iter::once(input);
     let outputs = p.run(&mut input);
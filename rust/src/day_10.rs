@@ -1,20 +1,66 @@
-use std::collections::btree_map::Entry;
-use std::collections::BTreeMap;
-use std::f32::consts::PI;
-
-use crate::grid::{Grid,Pos};
-
-pub fn solve(part: u8, data: Result<String, std::io::Error>) {
-    let grid: Grid<Roid> = data.expect("Couldn't read data").parse().unwrap();
-    let best_pos = grid.solve().unwrap();
-    match part {
-        1 => println!("{}", grid.count_visible(best_pos)),
-        2 => println!("{}", grid.lazer(best_pos)[199]),
-        _ => (),
+use std::cell::OnceCell;
+use std::collections::{BTreeMap, HashSet};
+
+use crate::grid::{self, Grid, Pos, Ray};
+use crate::solution::{Answer, Error, Solution};
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+
+    type Input = Field;
+
+    fn parse(data: &str) -> Result<Self::Input, Error> {
+        let grid: Grid<Roid> = data.parse().map_err(|e| Error::Parse(format!("{}", e)))?;
+        Ok(Field::new(grid))
+    }
+
+    fn part_1(field: &Self::Input) -> Result<Answer, Error> {
+        let (_, visible) = field.best_station()?;
+        Ok(visible.to_string())
+    }
+
+    fn part_2(field: &Self::Input) -> Result<Answer, Error> {
+        let (best_pos, _) = field.best_station()?;
+        field
+            .grid
+            .vaporise(&best_pos, &Sweep::default())
+            .nth(200)
+            .map(|shot| (shot.pos.x * 100 + shot.pos.y).to_string())
+            .ok_or_else(|| Error::NoAnswer("fewer than 200 asteroids".to_string()))
     }
 }
 
-#[derive(Debug)]
+/// The asteroid field, with the best station found the first time a part
+/// asks for it, so that the search is timed as solving rather than parsing.
+pub struct Field {
+    pub grid: Grid<Roid>,
+    station: OnceCell<Option<(Pos, usize)>>,
+}
+
+impl Field {
+    pub fn new(grid: Grid<Roid>) -> Self {
+        Field {
+            grid,
+            station: OnceCell::new(),
+        }
+    }
+
+    /// Where the station is best placed, and how many asteroids it sees.
+    pub fn station(&self) -> Option<(Pos, usize)> {
+        *self
+            .station
+            .get_or_init(|| self.grid.best().map(|(&pos, visible)| (pos, visible)))
+    }
+
+    fn best_station(&self) -> Result<(Pos, usize), Error> {
+        self.station()
+            .ok_or_else(|| Error::NoAnswer("there are no asteroids".to_string()))
+    }
+}
+
+#[derive(Debug, PartialEq)]
 pub struct Roid;
 
 impl std::fmt::Display for Roid {
@@ -50,78 +96,332 @@ impl std::str::FromStr for Roid {
 }
 
 impl<T> Grid<T> {
-    pub fn count_visible<'a>(&self, from: &Pos) -> usize {
-        let mut angles = std::collections::HashSet::new();
+    pub fn count_visible(&self, from: &Pos) -> usize {
+        let rays: HashSet<Ray> = self
+            .0
+            .keys()
+            .filter_map(|to| Ray::between(from, to))
+            .collect();
+        rays.len()
+    }
+
+    /// The number of asteroids visible from each asteroid, as a heatmap.
+    pub fn to_counts(&self) -> Grid<usize> {
+        let visibility = Visibility::new(self.0.keys().copied().collect());
+        let counts = visibility.counts(threads());
+        Grid(visibility.roids.into_iter().zip(counts).collect())
+    }
+
+    /// The asteroid that can see the most others, and how many it can see.
+    /// Ties go to the asteroid nearest the top, then the left.
+    pub fn best(&self) -> Option<(&Pos, usize)> {
+        let counts = self.to_counts();
+        let (pos, count) = counts
+            .0
+            .into_iter()
+            .max_by_key(|&(p, count)| (count, std::cmp::Reverse((p.y, p.x))))?;
+        self.0.get_key_value(&pos).map(|(pos, _)| (pos, count))
+    }
+
+    pub fn solve(&self) -> Option<&Pos> {
+        self.best().map(|(pos, _)| pos)
+    }
+
+    /// The asteroids in the order a laser at `from` destroys them.
+    pub fn vaporise(&self, from: &Pos, sweep: &Sweep) -> Vaporisation<'_> {
+        let mut by_ray: BTreeMap<Ray, Vec<&Pos>> = BTreeMap::new();
         for to in self.0.keys() {
-            if from == to {
-                continue;
-            }
-            let a = from.angle_to(to);
-            let a = (a * 1000.) as i32;
-            if !angles.contains(&a) {
-                angles.insert(a);
+            if let Some(ray) = Ray::between(from, to) {
+                by_ray.entry(ray).or_default().push(to);
             }
         }
-        angles.len()
-    }
 
-    fn to_counts(&self) -> Grid<usize> {
-        Grid(
-            self.0
-                .iter()
-                .map(|(p, _)| (*p, self.count_visible(p)))
+        let mut rays: Vec<Ray> = by_ray.keys().copied().collect();
+        if sweep.rotation == Rotation::CounterClockwise {
+            rays.reverse();
+        }
+        let first = rays
+            .iter()
+            .position(|ray| match sweep.rotation {
+                Rotation::Clockwise => *ray >= sweep.start,
+                Rotation::CounterClockwise => *ray <= sweep.start,
+            })
+            .unwrap_or(0);
+        rays.rotate_left(first);
+
+        // The nearest asteroid on each ray goes in the first rotation, the
+        // next nearest in the second, and so on.
+        let mut shots = vec![];
+        for (order, ray) in rays.iter().enumerate() {
+            let roids = by_ray.get_mut(ray).unwrap();
+            roids.sort_by_key(|p| from.dist_to(p));
+            for (rotation, pos) in roids.iter().enumerate() {
+                shots.push((rotation + 1, order, *pos));
+            }
+        }
+        shots.sort_by_key(|&(rotation, order, _)| (rotation, order));
+        Vaporisation(
+            shots
+                .into_iter()
+                .map(|(rotation, _, pos)| Shot { pos, rotation })
                 .collect(),
         )
     }
 
-    pub fn solve(&self) -> Option<&Pos> {
-        self.0.keys().max_by_key(|p| self.count_visible(p))
+    pub fn lazer(&self, from: &Pos) -> Vec<&Pos> {
+        self.vaporise(from, &Sweep::default())
+            .0
+            .into_iter()
+            .map(|shot| shot.pos)
+            .collect()
     }
+}
 
-    pub fn lazer(&self, from: &Pos) -> Vec<&Pos> {
-        let mut by_angle: BTreeMap<i32, Vec<&Pos>> = BTreeMap::new();
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Rotation {
+    Clockwise,
+    CounterClockwise,
+}
 
-        for to in self.0.keys() {
-            if from == to {
-                continue;
-            }
-            let a = from.angle_to(to);
-            let a = (a * 1000.) as i32;
-            by_angle
-                .entry(a)
-                .and_modify(|ps| ps.push(&to))
-                .or_insert(vec![to]);
+/// Which way the laser turns, and the direction it fires in first.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sweep {
+    pub rotation: Rotation,
+    pub start: Ray,
+}
+
+/// Clockwise, starting straight up, as in the puzzle.
+impl Default for Sweep {
+    fn default() -> Self {
+        Sweep {
+            rotation: Rotation::Clockwise,
+            start: Ray::UP,
         }
+    }
+}
 
-        for (_angle, roids) in by_angle.iter_mut() {
-            roids.sort_by_key(|p| from.dist_to(p));
-            roids.reverse();
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Shot<'a> {
+    pub pos: &'a Pos,
+    /// The rotation of the laser the asteroid is destroyed in, from 1.
+    pub rotation: usize,
+}
+
+/// Every asteroid, in the order they're destroyed.
+#[derive(Debug, PartialEq)]
+pub struct Vaporisation<'a>(pub Vec<Shot<'a>>);
+
+impl<'a> Vaporisation<'a> {
+    /// The `n`th asteroid destroyed, counting from 1.
+    pub fn nth(&self, n: usize) -> Option<&Shot<'a>> {
+        n.checked_sub(1).and_then(|i| self.0.get(i))
+    }
+
+    /// When the asteroid at `pos` is destroyed: its place in the order,
+    /// counting from 1, and the shot that destroys it.
+    pub fn find(&self, pos: &Pos) -> Option<(usize, &Shot<'a>)> {
+        self.0
+            .iter()
+            .enumerate()
+            .find(|(_, shot)| shot.pos == pos)
+            .map(|(i, shot)| (i + 1, shot))
+    }
+
+    /// The order as CSV, with columns `n,x,y,rotation`.
+    pub fn to_csv(&self) -> String {
+        let mut csv = "n,x,y,rotation\n".to_string();
+        for (i, shot) in self.0.iter().enumerate() {
+            csv.push_str(&format!(
+                "{},{},{},{}\n",
+                i + 1,
+                shot.pos.x,
+                shot.pos.y,
+                shot.rotation
+            ));
         }
+        csv
+    }
+}
+
+fn threads() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get())
+}
 
-        let mut destroyed_roids = vec![];
+/// The most slots a table of directions may have: each thread counting with
+/// the tables has its own table of this size.
+const MAX_TABLE: usize = 1 << 20;
+
+/// Tables are only used when there are at most this many slots for each
+/// asteroid, so sparse fields don't pay for their empty space.
+const MAX_SLOTS_PER_ROID: usize = 64;
+
+/// Draw the counts from `to_counts` as a heatmap, each asteroid shaded from
+/// 0, for the fewest asteroids seen, to 9, for the most.
+pub fn render_counts(counts: &Grid<usize>) -> String {
+    let min = counts.0.values().copied().min().unwrap_or(0);
+    let max = counts.0.values().copied().max().unwrap_or(0);
+    let shades: Grid<char> = Grid(
+        counts
+            .0
+            .iter()
+            .map(|(&pos, &count)| {
+                let shade = ((count - min) * 9).checked_div(max - min).unwrap_or(9);
+                (pos, std::char::from_digit(shade as u32, 10).unwrap())
+            })
+            .collect(),
+    );
+    shades
+        .render()
+        .axes()
+        .legend(9, &format!("sees {} asteroids", max))
+        .legend(0, &format!("sees {} asteroids", min))
+        .to_string()
+}
 
-        loop {
-            let keys: Vec<i32> = by_angle.keys().map(|a| *a).collect();
-            if keys.is_empty() {
-                break;
+/// The asteroids to count visible asteroids between, along with tables shared
+/// between the threads counting them when the field is dense enough.
+pub struct Visibility {
+    pub roids: Vec<Pos>,
+    /// `None` if the field is too sparse or spread out for tables, in which
+    /// case each station collects the rays it sees in a set.
+    tables: Option<Tables>,
+}
+
+/// Every offset between two asteroids reduced by its gcd once, up front, with
+/// each reduced direction having a slot to mark it seen.
+struct Tables {
+    /// The largest offset between two asteroids in each axis.
+    max_dx: usize,
+    max_dy: usize,
+    /// `(dx, dy)` divided by their gcd, at `dx * (max_dy + 1) + dy`, for
+    /// non-negative offsets.
+    reduced: Vec<(i32, i32)>,
+}
+
+impl Tables {
+    /// Tables for `roids`, if they aren't too large for how many asteroids
+    /// there are.
+    fn new(roids: &[Pos]) -> Option<Self> {
+        let span = |coord: fn(&Pos) -> i32| {
+            let min = roids.iter().map(coord).min().unwrap_or(0);
+            let max = roids.iter().map(coord).max().unwrap_or(0);
+            max.abs_diff(min) as usize
+        };
+        let (max_dx, max_dy) = (span(|p| p.x), span(|p| p.y));
+        let slots = max_dx
+            .checked_mul(2)
+            .and_then(|w| (w + 1).checked_mul(max_dy.checked_mul(2)? + 1))?;
+        if slots > MAX_TABLE || slots > roids.len().saturating_mul(MAX_SLOTS_PER_ROID) {
+            return None;
+        }
+
+        let mut reduced = Vec::with_capacity((max_dx + 1) * (max_dy + 1));
+        for dx in 0..=max_dx as i32 {
+            for dy in 0..=max_dy as i32 {
+                let g = std::cmp::max(grid::gcd(dx, dy), 1);
+                reduced.push((dx / g, dy / g));
             }
-            for angle in keys {
-                if let Entry::Occupied(mut entry) = by_angle.entry(angle) {
-                    let roids = entry.get_mut();
-                    if let Some(roid) = roids.pop() {
-                        destroyed_roids.push(roid);
-                    }
-                    if roids.is_empty() {
-                        entry.remove_entry();
-                    }
+        }
+        Some(Tables {
+            max_dx,
+            max_dy,
+            reduced,
+        })
+    }
+
+    fn count_from(&self, roids: &[Pos], stations: &[Pos]) -> Vec<usize> {
+        let rows = 2 * self.max_dy + 1;
+        // The stamp of the last station to have seen each direction.
+        let mut seen = vec![0u32; (2 * self.max_dx + 1) * rows];
+        let mut counts = Vec::with_capacity(stations.len());
+        for (stamp, from) in (1..).zip(stations) {
+            let mut count = 0;
+            for to in roids.iter() {
+                let (dx, dy) = (to.x - from.x, to.y - from.y);
+                if dx == 0 && dy == 0 {
+                    continue;
+                }
+                let (rx, ry) = self.reduced
+                    [dx.unsigned_abs() as usize * (self.max_dy + 1) + dy.unsigned_abs() as usize];
+                let slot = (dx.signum() * rx + self.max_dx as i32) as usize * rows
+                    + (dy.signum() * ry + self.max_dy as i32) as usize;
+                if seen[slot] != stamp {
+                    seen[slot] = stamp;
+                    count += 1;
                 }
             }
+            counts.push(count);
         }
+        counts
+    }
+}
+
+impl Visibility {
+    pub fn new(mut roids: Vec<Pos>) -> Self {
+        // In reading order, neighbouring asteroids have neighbouring offsets
+        // from any station, so the tables are read mostly in order.
+        roids.sort_by_key(|p| (p.y, p.x));
+        let tables = Tables::new(&roids);
+        Visibility { roids, tables }
+    }
+
+    /// The number of asteroids visible from each of `roids`, in the same
+    /// order (which is reading order), counted on up to `threads` threads.
+    pub fn counts(&self, threads: usize) -> Vec<usize> {
+        let chunk = std::cmp::max(1, self.roids.len().div_ceil(std::cmp::max(threads, 1)));
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = self
+                .roids
+                .chunks(chunk)
+                .map(|stations| scope.spawn(move || self.count_from(stations)))
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|h| h.join().unwrap())
+                .collect()
+        })
+    }
 
-        destroyed_roids
+    fn count_from(&self, stations: &[Pos]) -> Vec<usize> {
+        match &self.tables {
+            Some(tables) => tables.count_from(&self.roids, stations),
+            None => stations
+                .iter()
+                .map(|from| {
+                    let rays: HashSet<Ray> = self
+                        .roids
+                        .iter()
+                        .filter_map(|to| Ray::between(from, to))
+                        .collect();
+                    rays.len()
+                })
+                .collect(),
+        }
     }
 }
 
+/// A `width` by `height` field of asteroids, each cell having one with
+/// probability `density`, generated deterministically from `seed`.
+pub fn generate_field(width: i32, height: i32, density: f64, seed: u64) -> Grid<Roid> {
+    // xorshift64*, which is plenty random for scattering asteroids.
+    let mut state = seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1;
+    let mut random = move || {
+        state ^= state >> 12;
+        state ^= state << 25;
+        state ^= state >> 27;
+        (state.wrapping_mul(0x2545_f491_4f6c_dd1d) >> 11) as f64 / (1u64 << 53) as f64
+    };
+    let mut grid = Grid::new();
+    for y in 0..height {
+        for x in 0..width {
+            if random() < density {
+                grid.0.insert(Pos::new(x, y), Roid);
+            }
+        }
+    }
+    grid
+}
+
 mod part_1 {
 
     #[cfg(test)]
@@ -133,20 +433,25 @@ mod part_1 {
             // ...
             // .x.
             // ...
-            let angles = [
-                Pos::new(1, 1).angle_to(&Pos::new(1, 0)) / PI,
-                Pos::new(1, 1).angle_to(&Pos::new(2, 0)) / PI,
-                Pos::new(1, 1).angle_to(&Pos::new(2, 1)) / PI,
-                Pos::new(1, 1).angle_to(&Pos::new(2, 2)) / PI,
-                Pos::new(1, 1).angle_to(&Pos::new(1, 2)) / PI,
-                Pos::new(1, 1).angle_to(&Pos::new(0, 2)) / PI,
-                Pos::new(1, 1).angle_to(&Pos::new(0, 1)) / PI,
-                Pos::new(1, 1).angle_to(&Pos::new(0, 0)) / PI,
+            let from = Pos::new(1, 1);
+            let clockwise = [
+                Pos::new(1, 0),
+                Pos::new(2, 0),
+                Pos::new(2, 1),
+                Pos::new(2, 2),
+                Pos::new(1, 2),
+                Pos::new(0, 2),
+                Pos::new(0, 1),
+                Pos::new(0, 0),
             ];
-            assert_eq!(
-                angles,
-                [0.0, 0.2500001, 0.50000006, 0.7500001, 0.99999994, 1.25, 1.5000001, 1.75]
-            );
+            let rays: Vec<Ray> = clockwise
+                .iter()
+                .map(|to| Ray::between(&from, to).unwrap())
+                .collect();
+            let mut sorted = rays.clone();
+            sorted.reverse();
+            sorted.sort();
+            assert_eq!(sorted, rays);
         }
 
         #[test]
@@ -271,6 +576,65 @@ mod part_1 {
             let best = grid.solve();
             assert_eq!(best.map(|p| grid.count_visible(p)), Some(210));
             assert_eq!(grid.solve(), Some(&Pos::new(11, 13)));
+            assert_eq!(grid.best(), Some((&Pos::new(11, 13), 210)));
+
+            let field = Day10::parse(BIG_GRID).unwrap();
+            assert_eq!(field.station(), Some((Pos::new(11, 13), 210)));
+            assert_eq!(Day10::part_1(&field), Ok("210".to_string()));
+        }
+
+        #[test]
+        fn test_render_counts() {
+            let grid: Grid<Roid> = ".#..#\n.....\n#####\n....#\n...##".parse().unwrap();
+            let heatmap = render_counts(&grid.to_counts());
+            assert_eq!(
+                heatmap,
+                "  01234\n0 .6..6\n1 .....\n2 36660\n3 ....6\n4 ...96\n\n\
+                 9 sees 8 asteroids\n0 sees 5 asteroids\n"
+            );
+        }
+
+        #[test]
+        fn test_counts_sparse() {
+            // Too spread out for tables, which would have trillions of slots.
+            let grid: Grid<Roid> = Grid(
+                [
+                    (0, 0),
+                    (1_000_000, 0),
+                    (2_000_000, 0),
+                    (0, 1_000_000),
+                    (-3, -7),
+                ]
+                .iter()
+                .map(|&(x, y)| (Pos::new(x, y), Roid))
+                .collect(),
+            );
+            let visibility = Visibility::new(grid.0.keys().copied().collect());
+            assert!(visibility.tables.is_none());
+            let counts = grid.to_counts();
+            for (pos, &count) in counts.0.iter() {
+                assert_eq!(count, grid.count_visible(pos), "from {}", pos);
+            }
+            assert_eq!(counts.0[&Pos::new(1_000_000, 0)], 4);
+
+            let far = [Pos::new(i32::MIN, 0), Pos::new(i32::MAX, 0)];
+            assert!(Visibility::new(far.to_vec()).tables.is_none());
+        }
+
+        #[test]
+        fn test_counts_generated() {
+            let grid = generate_field(40, 30, 0.3, 7);
+            assert!(grid.0.len() > 100);
+            let counts = grid.to_counts();
+            for (pos, &count) in counts.0.iter() {
+                assert_eq!(count, grid.count_visible(pos), "from {}", pos);
+            }
+            assert_eq!(generate_field(40, 30, 0.3, 7), grid);
+
+            let visibility = Visibility::new(grid.0.keys().copied().collect());
+            assert!(visibility.tables.is_some());
+            assert_eq!(visibility.counts(1), visibility.counts(5));
+            assert_eq!(Grid::<Roid>::new().best(), None);
         }
     }
 }
@@ -323,6 +687,63 @@ mod part_2 {
             assert_eq!(destroyed_roids[200], &Pos::new(10, 9));
             assert_eq!(destroyed_roids[298], &Pos::new(11, 1));
             assert_eq!(destroyed_roids.len(), 299);
+
+            let field = Day10::parse(part_1::tests::BIG_GRID).unwrap();
+            assert_eq!(Day10::part_2(&field), Ok("802".to_string()));
+        }
+
+        #[test]
+        fn test_vaporise() {
+            let grid: Grid<Roid> = part_1::tests::BIG_GRID.parse().unwrap();
+            let from = Pos::new(11, 13);
+            let order = grid.vaporise(&from, &Sweep::default());
+            assert_eq!(order.nth(200).map(|shot| shot.pos), Some(&Pos::new(8, 2)));
+            assert_eq!(order.nth(0), None);
+            assert_eq!(order.nth(300), None);
+            let (n, shot) = order.find(&Pos::new(11, 1)).unwrap();
+            assert_eq!(n, 299);
+            assert!(shot.rotation > 1);
+            assert_eq!(order.find(&from), None);
+            assert_eq!(order.nth(1).unwrap().rotation, 1);
+
+            let csv = order.to_csv();
+            assert_eq!(csv.lines().count(), 300);
+            assert!(csv.starts_with("n,x,y,rotation\n1,11,12,1\n2,12,1,1\n"));
+        }
+
+        #[test]
+        fn test_sweeps() {
+            // .#.
+            // #X#
+            // .##
+            let grid: Grid<Roid> = ".#.\n###\n.##\n".parse().unwrap();
+            let from = Pos::new(1, 1);
+            let order = |rotation, start| {
+                let sweep = Sweep { rotation, start };
+                grid.vaporise(&from, &sweep)
+                    .0
+                    .iter()
+                    .map(|shot| shot.pos.to_string())
+                    .collect::<Vec<_>>()
+            };
+            let up = Ray::UP;
+            assert_eq!(
+                order(Rotation::Clockwise, up),
+                ["1,0", "2,1", "2,2", "1,2", "0,1"]
+            );
+            assert_eq!(
+                order(Rotation::CounterClockwise, up),
+                ["1,0", "0,1", "1,2", "2,2", "2,1"]
+            );
+            // Starting between directions fires at the next one round.
+            assert_eq!(
+                order(Rotation::Clockwise, Ray::new(1, 2).unwrap()),
+                ["1,2", "0,1", "1,0", "2,1", "2,2"]
+            );
+            assert_eq!(
+                order(Rotation::CounterClockwise, Ray::new(2, 1).unwrap()),
+                ["2,1", "1,0", "0,1", "1,2", "2,2"]
+            );
         }
     }
 }
@@ -1,48 +1,73 @@
-use crate::grid::{Grid, Pos};
+use crate::animate::{self, Animation};
+use crate::grid::{Direction, Grid, Pos, Turn};
 use crate::intcode;
+use crate::ocr;
+use crate::solution::{Answer, Error, Solution};
 
-pub fn solve(part: u8, data: Result<String, std::io::Error>) {
-    let memory: Vec<i64> = data
-        .expect("couldn't read data file")
-        .split(",")
-        .map(|s| s.parse::<i64>().expect("bad data"))
-        .collect();
-    let mut robot = Robot::new();
-    let mut program = intcode::Program::new(memory);
-    let mut grid = Grid::new();
-    match part {
-        1 => {
-            robot.run(&mut program, &mut grid);
-            println!("{}", grid.0.len());
-        }
-        2 => {
-            grid.0.insert(robot.pos, Color::White);
-            robot.run(&mut program, &mut grid);
-            println!("{}", grid);
-        }
-        _ => (),
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+
+    type Input = Vec<i64>;
+
+    fn parse(data: &str) -> Result<Self::Input, Error> {
+        Ok(intcode::parse(data)?)
+    }
+
+    fn part_1(memory: &Self::Input) -> Result<Answer, Error> {
+        Ok(paint(memory, 1, |_, _| ()))
+    }
+
+    fn part_2(memory: &Self::Input) -> Result<Answer, Error> {
+        Ok(paint(memory, 2, |_, _| ()))
     }
 }
 
-enum Direction {
-    North,
-    East,
-    South,
-    West,
+/// The letters painted on the hull.
+fn registration(grid: &Grid<Color>) -> Answer {
+    let pixels = ocr::grid_pixels(grid, |c| matches!(c, Color::White));
+    // Fall back to the painted hull if it can't be read.
+    ocr::recognize(&pixels).unwrap_or_else(|_| grid.render().empty(' ').to_string())
 }
 
-enum Turn {
-    Left,
-    Right,
+/// Paint the hull for `part`, calling `on_move` after each move of the robot.
+fn paint(memory: &[i64], part: u8, on_move: impl FnMut(&Grid<Color>, &Robot)) -> Answer {
+    let mut grid = Grid::new();
+    if part == 2 {
+        grid.0.insert(Pos::new(0, 0), Color::White);
+    }
+    let mut robot = Robot::new();
+    let mut program = intcode::Program::new(memory.to_vec());
+    robot.run(&mut program, &mut grid, on_move);
+    match part {
+        2 => registration(&grid),
+        _ => grid.0.len().to_string(),
+    }
 }
 
-impl Turn {
-    fn of_output(o: i64) -> Option<Turn> {
-        match o {
-            0 => Some(Turn::Left),
-            1 => Some(Turn::Right),
-            _ => None,
+/// Draw the robot painting the hull, then give the answer to `part`.
+pub fn animate<W: std::io::Write>(
+    memory: &[i64],
+    part: u8,
+    animation: &mut Animation<W>,
+) -> Result<Answer, String> {
+    let mut result = Ok(());
+    let answer = paint(memory, part, |grid, robot| {
+        if result.is_ok() {
+            let actor = (robot.pos, animate::arrow(robot.facing));
+            result = animation.frame(&animate::render(grid, &[actor]));
         }
+    });
+    result.map_err(|e| e.to_string())?;
+    Ok(answer)
+}
+
+fn turn_of_output(o: i64) -> Option<Turn> {
+    match o {
+        0 => Some(Turn::Left),
+        1 => Some(Turn::Right),
+        _ => None,
     }
 }
 
@@ -53,7 +78,7 @@ enum Color {
 }
 
 impl Color {
-    fn to_input(&self) -> i64 {
+    fn to_input(self) -> i64 {
         match self {
             Color::Black => 0,
             Color::White => 1,
@@ -96,32 +121,20 @@ impl Robot {
     }
 
     fn turn(&mut self, t: Turn) {
-        self.facing = {
-            match (&self.facing, t) {
-                (Direction::North, Turn::Right) => Direction::East,
-                (Direction::East, Turn::Right) => Direction::South,
-                (Direction::South, Turn::Right) => Direction::West,
-                (Direction::West, Turn::Right) => Direction::North,
-                (Direction::North, Turn::Left) => Direction::West,
-                (Direction::East, Turn::Left) => Direction::North,
-                (Direction::South, Turn::Left) => Direction::East,
-                (Direction::West, Turn::Left) => Direction::South,
-            }
-        }
+        self.facing = self.facing.turn(t);
     }
 
     fn step(&mut self) {
-        self.pos = {
-            match &self.facing {
-                Direction::North => Pos::new(self.pos.x, self.pos.y - 1),
-                Direction::East => Pos::new(self.pos.x + 1, self.pos.y),
-                Direction::South => Pos::new(self.pos.x, self.pos.y + 1),
-                Direction::West => Pos::new(self.pos.x - 1, self.pos.y),
-            }
-        }
+        self.pos = self.pos + self.facing;
     }
 
-    fn run(&mut self, program: &mut intcode::Program, grid: &mut Grid<Color>) {
+    /// Run the robot, calling `on_move` after each move.
+    fn run(
+        &mut self,
+        program: &mut intcode::Program,
+        grid: &mut Grid<Color>,
+        mut on_move: impl FnMut(&Grid<Color>, &Robot),
+    ) {
         let mut output_recieved = true;
 
         loop {
@@ -140,9 +153,10 @@ impl Robot {
                             .or_insert(color);
                     } else {
                         // Second output is direction to turn
-                        let turn = Turn::of_output(o).expect("Invalid output");
+                        let turn = turn_of_output(o).expect("Invalid output");
                         self.turn(turn);
                         self.step();
+                        on_move(grid, self);
                     }
                     program.state = intcode::State::Running;
                     output_recieved = true;
@@ -1,32 +1,40 @@
 use std::collections::HashSet;
 
-pub fn solve(part: u8, data: Result<String, std::io::Error>) {
-    let mut positions = vec![];
+use crate::solution::{Answer, Error, Solution};
 
-    for line in data.expect("Couldn't read data file").lines() {
-        let p: Vec3 = line.parse().unwrap();
-        positions.push(p);
-    }
+pub struct Day12;
 
-    let mut system = MoonSystem::from_positions(positions);
+impl Solution for Day12 {
+    const DAY: u8 = 12;
 
-    match part {
-        1 => {
-            for _ in 0..1000 {
-                system.step();
-            }
-            println!("{}", system.total_energy());
-        }
-        2 => {
-            let repeats = steps_until_repeat(&mut system);
-            println!("{}", lcm3(repeats));
+    type Input = Vec<Vec3>;
+
+    fn parse(data: &str) -> Result<Self::Input, Error> {
+        data.lines()
+            .map(|line| {
+                line.parse()
+                    .map_err(|_| Error::Parse(format!("bad position: {}", line)))
+            })
+            .collect()
+    }
+
+    fn part_1(positions: &Self::Input) -> Result<Answer, Error> {
+        let mut system = MoonSystem::from_positions(positions.clone());
+        for _ in 0..1000 {
+            system.step();
         }
-        _ => (),
+        Ok(system.total_energy().to_string())
+    }
+
+    fn part_2(positions: &Self::Input) -> Result<Answer, Error> {
+        let mut system = MoonSystem::from_positions(positions.clone());
+        let repeats = steps_until_repeat(&mut system);
+        Ok(lcm3(repeats).to_string())
     }
 }
 
-#[derive(Debug, PartialEq)]
-struct Vec3([i32; 3]);
+#[derive(Debug, PartialEq, Clone)]
+pub struct Vec3([i32; 3]);
 
 impl Vec3 {
     fn zero() -> Self {
@@ -128,7 +136,7 @@ struct MoonSystem {
 
 impl MoonSystem {
     fn from_positions(ps: Vec<Vec3>) -> Self {
-        let moons = ps.into_iter().map(|p| Moon::new(p)).collect();
+        let moons = ps.into_iter().map(Moon::new).collect();
         Self { moons }
     }
 
@@ -194,9 +202,8 @@ fn steps_until_repeat(system: &mut MoonSystem) -> [i64; 3] {
                 }
             }
         }
-        match repeats {
-            [Some(x), Some(y), Some(z)] => return [x, y, z],
-            _ => (),
+        if let [Some(x), Some(y), Some(z)] = repeats {
+            return [x, y, z];
         }
         steps += 1;
         system.step();
@@ -1,31 +1,61 @@
+use crate::animate::{self, Animation};
 use crate::grid::{Grid, Pos};
-use crate::intcode::{Program, State};
+use crate::intcode::{self, Program, State};
+use crate::solution::{Answer, Error, Solution};
 
-pub fn solve(part: u8, data: Result<String, std::io::Error>) {
-    let memory: Vec<i64> = data
-        .expect("couldn't read data file")
-        .split(",")
-        .map(|s| s.parse::<i64>().expect("bad data"))
-        .collect();
-    let mut program = Program::new(memory);
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u8 = 13;
+
+    type Input = Vec<i64>;
+
+    fn parse(data: &str) -> Result<Self::Input, Error> {
+        Ok(intcode::parse(data)?)
+    }
+
+    fn part_1(memory: &Self::Input) -> Result<Answer, Error> {
+        Ok(play(memory, 1, |_, _| ()))
+    }
+
+    fn part_2(memory: &Self::Input) -> Result<Answer, Error> {
+        Ok(play(memory, 2, |_, _| ()))
+    }
+}
+
+/// Play the game for `part`, calling `on_frame` as `go` does. Part 1 only
+/// draws the screen and counts its blocks, part 2 plays until the blocks are
+/// gone and gives the score.
+fn play(memory: &[i64], part: u8, on_frame: impl FnMut(&Grid<Tile>, i64)) -> Answer {
+    let mut program = Program::new(memory.to_vec());
+    if part == 2 {
+        program.memory[0] = 2;
+    }
     let mut grid = Grid::new();
+    let score = go(&mut program, &mut grid, on_frame);
     match part {
-        1 => {
-            go(&mut program, &mut grid);
-            println!("{}", grid);
-            let num_block_tiles = grid.0.values().filter(|t| t.is_block()).count();
-            println!("{}", num_block_tiles);
-        },
-        2 => {
-            program.memory[0] = 2;
-            let score = go(&mut program, &mut grid);
-            println!("{}", grid);
-            println!("{}", score);
-        },
-        _ => (),
+        2 => score.to_string(),
+        _ => grid.0.values().filter(|t| t.is_block()).count().to_string(),
     }
 }
 
+/// Draw the game as it's played, then give the answer to `part`.
+pub fn animate<W: std::io::Write>(
+    memory: &[i64],
+    part: u8,
+    animation: &mut Animation<W>,
+) -> Result<Answer, String> {
+    let mut result = Ok(());
+    let answer = play(memory, part, |grid, score| {
+        if result.is_ok() {
+            let frame = format!("{}score: {}", animate::render(grid, &[]), score);
+            result = animation.frame(&frame);
+        }
+    });
+    result.map_err(|e| e.to_string())?;
+    Ok(answer)
+}
+
 enum Tile {
     Empty,
     Wall,
@@ -47,10 +77,7 @@ impl Tile {
     }
 
     fn is_block(&self) -> bool {
-        match self {
-            Tile::Block => true,
-            _ => false,
-        }
+        matches!(self, Tile::Block)
     }
 }
 
@@ -70,7 +97,13 @@ impl std::fmt::Display for Tile {
     }
 }
 
-fn go(program: &mut Program, grid: &mut Grid<Tile>) -> i64 {
+/// Run the game, calling `on_frame` with the screen and score whenever the
+/// game waits for the joystick, and when it ends.
+fn go(
+    program: &mut Program,
+    grid: &mut Grid<Tile>,
+    mut on_frame: impl FnMut(&Grid<Tile>, i64),
+) -> i64 {
     let mut next_x = None;
     let mut next_y = None;
     let mut score = 0;
@@ -108,6 +141,7 @@ fn go(program: &mut Program, grid: &mut Grid<Tile>) -> i64 {
                 program.state = State::Running;
             }
             State::WaitForInput(addr) => {
+                on_frame(grid, score);
                 if grid.0.values().filter(|t| t.is_block()).count() == 0 {
                     break
                 }
@@ -126,5 +160,6 @@ fn go(program: &mut Program, grid: &mut Grid<Tile>) -> i64 {
         }
 
     }
+    on_frame(grid, score);
     score
 }
@@ -0,0 +1,162 @@
+//! Writing grids and images as pictures: plain-text PPM and PGM (netpbm
+//! `P3` and `P2`), which most image viewers can open, and SVG.
+
+use std::fmt::Write;
+
+use crate::grid::{Grid, Pos};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+}
+
+impl std::fmt::Display for Rgb {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+/// Netpbm readers don't need lines to be short, but the format asks for it.
+const MAX_LINE: usize = 70;
+
+fn netpbm(magic: &str, rows: &[Vec<String>]) -> String {
+    let width = rows.first().map_or(0, |r| r.len());
+    let mut out = format!("{}\n{} {}\n255\n", magic, width, rows.len());
+    for row in rows {
+        let mut line = String::new();
+        for sample in row {
+            if !line.is_empty() && line.len() + sample.len() + 1 > MAX_LINE {
+                out.push_str(&line);
+                out.push('\n');
+                line.clear();
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(sample);
+        }
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out
+}
+
+/// A colour picture as a plain PPM, from rows of the same length.
+pub fn ppm(rows: &[Vec<Rgb>]) -> String {
+    let rows: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|Rgb(r, g, b)| format!("{} {} {}", r, g, b))
+                .collect()
+        })
+        .collect();
+    netpbm("P3", &rows)
+}
+
+/// A greyscale picture as a plain PGM, from rows of the same length.
+pub fn pgm(rows: &[Vec<u8>]) -> String {
+    let rows: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| row.iter().map(|v| v.to_string()).collect())
+        .collect();
+    netpbm("P2", &rows)
+}
+
+/// A picture as SVG, drawing each pixel as a `scale` by `scale` square, and
+/// leaving `None` pixels transparent.
+pub fn svg(rows: &[Vec<Option<Rgb>>], scale: u32) -> String {
+    let width = rows.first().map_or(0, |r| r.len());
+    let mut out = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}" shape-rendering="crispEdges">"#,
+        width as u32 * scale,
+        rows.len() as u32 * scale,
+        width,
+        rows.len()
+    );
+    out.push('\n');
+    for (y, row) in rows.iter().enumerate() {
+        for (x, colour) in row.iter().enumerate() {
+            if let Some(colour) = colour {
+                writeln!(
+                    out,
+                    r#"<rect x="{}" y="{}" width="1" height="1" fill="{}"/>"#,
+                    x, y, colour
+                )
+                .unwrap();
+            }
+        }
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+/// The grid's bounding box as rows of pixels.
+pub fn grid_rows<T, C>(grid: &Grid<T>, pixel: impl Fn(Option<&T>) -> C) -> Vec<Vec<C>> {
+    if grid.0.is_empty() {
+        return vec![];
+    }
+    let (min, max) = (grid.min_pos(), grid.max_pos());
+    (min.y..=max.y)
+        .map(|y| {
+            (min.x..=max.x)
+                .map(|x| pixel(grid.0.get(&Pos::new(x, y))))
+                .collect()
+        })
+        .collect()
+}
+
+impl<T> Grid<T> {
+    /// The grid as a PPM, coloured by `colour`, which is given `None` for
+    /// cells without a value.
+    pub fn to_ppm(&self, colour: impl Fn(Option<&T>) -> Rgb) -> String {
+        ppm(&grid_rows(self, colour))
+    }
+
+    pub fn to_pgm(&self, grey: impl Fn(Option<&T>) -> u8) -> String {
+        pgm(&grid_rows(self, grey))
+    }
+
+    pub fn to_svg(&self, scale: u32, colour: impl Fn(Option<&T>) -> Option<Rgb>) -> String {
+        svg(&grid_rows(self, colour), scale)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<char> {
+        "#.\n.#\n".parse().unwrap()
+    }
+
+    #[test]
+    fn test_netpbm() {
+        let grid = grid();
+        assert_eq!(
+            grid.to_pgm(|c| if c.is_some() { 255 } else { 0 }),
+            "P2\n2 2\n255\n255 0\n0 255\n"
+        );
+        assert_eq!(
+            grid.to_ppm(|c| c.map_or(Rgb::BLACK, |_| Rgb(255, 0, 10))),
+            "P3\n2 2\n255\n255 0 10 0 0 0\n0 0 0 255 0 10\n"
+        );
+        let wide = pgm(&[vec![255; 30]]);
+        assert!(wide.lines().all(|l| l.len() <= MAX_LINE));
+        assert_eq!(wide.split_whitespace().count(), 4 + 30);
+    }
+
+    #[test]
+    fn test_svg() {
+        let svg = grid().to_svg(10, |c| c.map(|_| Rgb::WHITE));
+        assert!(svg.starts_with(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="20" viewBox="0 0 2 2""#
+        ));
+        assert!(svg.contains(r##"<rect x="1" y="1" width="1" height="1" fill="#ffffff"/>"##));
+        assert_eq!(svg.matches("<rect").count(), 2);
+        assert!(svg.ends_with("</svg>\n"));
+    }
+}
@@ -1,7 +1,7 @@
-use std::collections::HashMap;
-use std::f32::consts::PI;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
 
-#[derive(Hash, PartialEq, Eq, Debug, Clone, Copy)]
+#[derive(Hash, PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy)]
 pub struct Pos {
     pub x: i32,
     pub y: i32,
@@ -12,12 +12,6 @@ impl Pos {
         Pos { x, y }
     }
 
-    pub fn angle_to(&self, other: &Pos) -> f32 {
-        let dx = other.x as f32 - self.x as f32;
-        let dy = other.y as f32 - self.y as f32;
-        (dy.atan2(dx) + PI * 5. / 2.) % (2. * PI)
-    }
-
     pub fn dist_to(&self, other: &Pos) -> i32 {
         let dx = other.x - self.x;
         let dy = other.y - self.y;
@@ -31,6 +25,210 @@ impl std::fmt::Display for Pos {
     }
 }
 
+#[derive(Debug, PartialEq)]
+pub struct PosParseError(pub String);
+
+impl std::fmt::Display for PosParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "not a position (expected x,y): {}", self.0)
+    }
+}
+
+impl std::error::Error for PosParseError {}
+
+/// Parses `x,y`, as positions are displayed.
+impl std::str::FromStr for Pos {
+    type Err = PosParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut coords = s.split(',').map(|c| c.trim().parse::<i32>());
+        match (coords.next(), coords.next(), coords.next()) {
+            (Some(Ok(x)), Some(Ok(y)), None) => Ok(Pos::new(x, y)),
+            _ => Err(PosParseError(s.to_string())),
+        }
+    }
+}
+
+/// The greatest common divisor of `a` and `b`, which is never negative.
+pub fn gcd(a: i32, b: i32) -> i32 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// The exact direction from one position to another, reduced so that every
+/// position along the same line of sight has the same ray.
+///
+/// Rays are ordered clockwise (with `y` increasing down the screen), starting
+/// from straight up. Rays are always reduced and never zero, so that rays in
+/// the same direction are equal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Ray {
+    dx: i32,
+    dy: i32,
+}
+
+impl Ray {
+    /// Straight up the screen.
+    pub const UP: Ray = Ray { dx: 0, dy: -1 };
+
+    /// The ray in the direction of `(dx, dy)`, or `None` if it's `(0, 0)`,
+    /// which has no direction.
+    pub fn new(dx: i32, dy: i32) -> Option<Self> {
+        match gcd(dx, dy) {
+            0 => None,
+            g => Some(Ray {
+                dx: dx / g,
+                dy: dy / g,
+            }),
+        }
+    }
+
+    /// The ray from `from` to `to`, or `None` if they're the same position.
+    pub fn between(from: &Pos, to: &Pos) -> Option<Self> {
+        Ray::new(to.x - from.x, to.y - from.y)
+    }
+
+    pub fn dx(&self) -> i32 {
+        self.dx
+    }
+
+    pub fn dy(&self) -> i32 {
+        self.dy
+    }
+
+    /// 0 for rays from straight up round to before straight down, 1 for
+    /// the rest.
+    fn half(&self) -> u8 {
+        if self.dx > 0 || (self.dx == 0 && self.dy < 0) {
+            0
+        } else {
+            1
+        }
+    }
+
+    /// Positive if `other` is clockwise of `self` by less than a half turn.
+    fn cross(&self, other: &Ray) -> i64 {
+        self.dx as i64 * other.dy as i64 - self.dy as i64 * other.dx as i64
+    }
+}
+
+impl Ord for Ray {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.half()
+            .cmp(&other.half())
+            .then_with(|| 0.cmp(&self.cross(other)))
+    }
+}
+
+impl PartialOrd for Ray {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A compass direction. North is up the screen, towards smaller `y`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Turn {
+    Left,
+    Right,
+}
+
+impl Direction {
+    /// Clockwise from north.
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    pub fn turn(self, turn: Turn) -> Self {
+        match turn {
+            Turn::Left => self.turn_left(),
+            Turn::Right => self.turn_right(),
+        }
+    }
+
+    pub fn turn_right(self) -> Self {
+        Direction::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Self {
+        Direction::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn reverse(self) -> Self {
+        Direction::ALL[(self as usize + 2) % 4]
+    }
+
+    pub fn is_vertical(self) -> bool {
+        matches!(self, Direction::North | Direction::South)
+    }
+
+    /// The change in position from one step in this direction.
+    pub fn offset(self) -> (i32, i32) {
+        match self {
+            Direction::North => (0, -1),
+            Direction::East => (1, 0),
+            Direction::South => (0, 1),
+            Direction::West => (-1, 0),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct DirectionParseError(pub String);
+
+impl std::fmt::Display for DirectionParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "not a direction: {}", self.0)
+    }
+}
+
+impl std::error::Error for DirectionParseError {}
+
+/// Parses `U`/`R`/`D`/`L` as well as `N`/`E`/`S`/`W`.
+impl std::str::FromStr for Direction {
+    type Err = DirectionParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "U" | "N" => Ok(Direction::North),
+            "R" | "E" => Ok(Direction::East),
+            "D" | "S" => Ok(Direction::South),
+            "L" | "W" => Ok(Direction::West),
+            _ => Err(DirectionParseError(s.to_string())),
+        }
+    }
+}
+
+impl Pos {
+    /// The position `steps` steps away in direction `dir`.
+    pub fn step(self, dir: Direction, steps: i32) -> Pos {
+        let (dx, dy) = dir.offset();
+        Pos::new(self.x + dx * steps, self.y + dy * steps)
+    }
+}
+
+impl std::ops::Add<Direction> for Pos {
+    type Output = Pos;
+
+    fn add(self, dir: Direction) -> Pos {
+        self.step(dir, 1)
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Grid<T>(pub HashMap<Pos, T>);
 
@@ -39,49 +237,249 @@ impl<T> Grid<T> {
         Grid(HashMap::new())
     }
 
-    fn min_pos(&self) -> Pos {
+    /// The top-left corner of the bounding box.
+    pub fn min_pos(&self) -> Pos {
         let min_x = self.0.keys().map(|p| p.x).min().unwrap_or(0);
         let min_y = self.0.keys().map(|p| p.y).min().unwrap_or(0);
         Pos::new(min_x, min_y)
     }
 
-    fn max_pos(&self) -> Pos {
+    /// The bottom-right corner of the bounding box.
+    pub fn max_pos(&self) -> Pos {
         let max_x = self.0.keys().map(|p| p.x).max().unwrap_or(0);
         let max_y = self.0.keys().map(|p| p.y).max().unwrap_or(0);
         Pos::new(max_x, max_y)
     }
 }
 
+impl<T> Default for Grid<T> {
+    fn default() -> Self {
+        Grid::new()
+    }
+}
+
 impl<T> std::fmt::Display for Grid<T>
 where
     T: std::fmt::Display,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
-        let min_p = self.min_pos();
-        let max_p = self.max_pos();
-        for y in min_p.y..max_p.y + 1 {
-            for x in min_p.x..max_p.x + 1 {
-                let p = Pos::new(x, y);
-                match self.0.get(&p) {
+        self.render().fmt(f)
+    }
+}
+
+/// Options for drawing a `Grid`, which is drawn by its `Display` impl.
+pub struct Renderer<'a, T> {
+    grid: &'a Grid<T>,
+    empty: char,
+    viewport: Option<(Pos, Pos)>,
+    axes: bool,
+    flip_y: bool,
+    legend: Vec<(String, String)>,
+}
+
+impl<T> Grid<T> {
+    /// Draw the grid's bounding box, with `.` for empty cells, unless
+    /// configured otherwise.
+    pub fn render(&self) -> Renderer<'_, T> {
+        Renderer {
+            grid: self,
+            empty: '.',
+            viewport: None,
+            axes: false,
+            flip_y: false,
+            legend: vec![],
+        }
+    }
+}
+
+impl<'a, T> Renderer<'a, T> {
+    /// Draw cells without a value as `glyph`.
+    pub fn empty(mut self, glyph: char) -> Self {
+        self.empty = glyph;
+        self
+    }
+
+    /// Draw the cells between `min` and `max` inclusive, rather than the
+    /// bounding box.
+    pub fn viewport(mut self, min: Pos, max: Pos) -> Self {
+        self.viewport = Some((min, max));
+        self
+    }
+
+    /// Label the rows and columns with their coordinates. Column labels are
+    /// written downwards.
+    pub fn axes(mut self) -> Self {
+        self.axes = true;
+        self
+    }
+
+    /// Draw larger `y` higher up.
+    pub fn flip_y(mut self) -> Self {
+        self.flip_y = true;
+        self
+    }
+
+    /// Explain what a glyph means, below the grid.
+    pub fn legend(mut self, glyph: impl std::fmt::Display, meaning: &str) -> Self {
+        self.legend.push((glyph.to_string(), meaning.to_string()));
+        self
+    }
+}
+
+impl<'a, T> std::fmt::Display for Renderer<'a, T>
+where
+    T: std::fmt::Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let (min, max) = self
+            .viewport
+            .unwrap_or_else(|| (self.grid.min_pos(), self.grid.max_pos()));
+        let mut ys: Vec<i32> = (min.y..=max.y).collect();
+        if self.flip_y {
+            ys.reverse();
+        }
+
+        let label_width = if self.axes {
+            ys.iter().map(|y| y.to_string().len()).max().unwrap_or(0)
+        } else {
+            0
+        };
+        let margin = if self.axes { label_width + 1 } else { 0 };
+        if self.axes {
+            let labels: Vec<String> = (min.x..=max.x).map(|x| x.to_string()).collect();
+            let height = labels.iter().map(|l| l.len()).max().unwrap_or(0);
+            for i in 0..height {
+                let line: String = labels
+                    .iter()
+                    .map(|l| {
+                        let pad = height - l.len();
+                        if i < pad {
+                            ' '
+                        } else {
+                            l.as_bytes()[i - pad] as char
+                        }
+                    })
+                    .collect();
+                writeln!(f, "{:margin$}{}", "", line.trim_end(), margin = margin)?;
+            }
+        }
+
+        for y in ys {
+            if self.axes {
+                write!(f, "{:>width$} ", y, width = label_width)?;
+            }
+            for x in min.x..=max.x {
+                match self.grid.0.get(&Pos::new(x, y)) {
                     Some(v) => write!(f, "{}", v),
-                    None => write!(f, "."),
+                    None => write!(f, "{}", self.empty),
                 }?
             }
-            writeln!(f, "")?;
+            writeln!(f)?;
+        }
+
+        if !self.legend.is_empty() {
+            writeln!(f)?;
+            for (glyph, meaning) in self.legend.iter() {
+                writeln!(f, "{} {}", glyph, meaning)?;
+            }
         }
         Ok(())
     }
 }
 
-#[derive(Debug)]
-pub enum GridParseError<E> {
-    Utf8Error(std::str::Utf8Error),
-    ItemError(E),
+/// A cell that couldn't be parsed, with its 1-based line and column.
+#[derive(Debug, PartialEq)]
+pub struct GridParseError<E> {
+    pub line: usize,
+    pub column: usize,
+    pub err: E,
+}
+
+impl<E> std::fmt::Display for GridParseError<E>
+where
+    E: std::fmt::Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "Couldn't parse grid at line {}, column {}: {}",
+            self.line, self.column, self.err
+        )
+    }
 }
 
-impl<E> From<std::str::Utf8Error> for GridParseError<E> {
-    fn from(err: std::str::Utf8Error) -> Self {
-        GridParseError::Utf8Error(err)
+impl<E> std::error::Error for GridParseError<E> where E: std::fmt::Debug + std::fmt::Display {}
+
+/// Options for reading a `Grid` from text, one character per cell.
+pub struct Parser {
+    empty: Option<char>,
+    origin: Pos,
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Parser {
+            empty: Some('.'),
+            origin: Pos::new(0, 0),
+        }
+    }
+}
+
+impl Parser {
+    /// Skip `.` cells, and put the first character at `0,0`, unless
+    /// configured otherwise.
+    pub fn new() -> Self {
+        Parser::default()
+    }
+
+    /// Skip cells holding `glyph`.
+    pub fn empty(mut self, glyph: char) -> Self {
+        self.empty = Some(glyph);
+        self
+    }
+
+    /// Parse every cell.
+    pub fn keep_all(mut self) -> Self {
+        self.empty = None;
+        self
+    }
+
+    /// Put the first character of the first line at `origin`.
+    pub fn origin(mut self, origin: Pos) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    /// Parse each cell with `cell`.
+    pub fn parse_with<T, E>(
+        &self,
+        s: &str,
+        cell: impl Fn(char) -> Result<T, E>,
+    ) -> Result<Grid<T>, GridParseError<E>> {
+        let mut grid = HashMap::new();
+        for (y, line) in s.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                if Some(c) == self.empty {
+                    continue;
+                }
+                let v = cell(c).map_err(|err| GridParseError {
+                    line: y + 1,
+                    column: x + 1,
+                    err,
+                })?;
+                let pos = Pos::new(self.origin.x + x as i32, self.origin.y + y as i32);
+                grid.insert(pos, v);
+            }
+        }
+        Ok(Grid(grid))
+    }
+
+    /// Parse each cell with `FromStr`.
+    pub fn parse<T>(&self, s: &str) -> Result<Grid<T>, GridParseError<T::Err>>
+    where
+        T: std::str::FromStr,
+    {
+        self.parse_with(s, |c| c.to_string().parse())
     }
 }
 
@@ -92,20 +490,920 @@ where
     type Err = GridParseError<T::Err>;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut roids = HashMap::new();
-        for (y, line) in s.lines().enumerate() {
-            for (x, byte) in line.as_bytes().iter().enumerate() {
-                match byte {
-                    b'.' => (),
-                    _ => {
-                        let v = std::str::from_utf8(std::slice::from_ref(byte))?;
-                        let v = v.parse().map_err(|e| GridParseError::ItemError(e))?;
-                        let pos = Pos::new(x as i32, y as i32);
-                        roids.insert(pos, v);
-                    }
+        Parser::new().parse(s)
+    }
+}
+
+/// A grid stored densely, row by row, covering the `width` by `height`
+/// rectangle whose top-left cell is `origin`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DenseGrid<T> {
+    origin: Pos,
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+/// A dense grid of this size would have more cells than fit in a `usize`.
+#[derive(Debug, PartialEq)]
+pub struct TooLarge {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl std::fmt::Display for TooLarge {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a {}x{} grid is too large", self.width, self.height)
+    }
+}
+
+impl std::error::Error for TooLarge {}
+
+fn cell_count(width: usize, height: usize) -> Result<usize, TooLarge> {
+    width.checked_mul(height).ok_or(TooLarge { width, height })
+}
+
+impl<T> DenseGrid<T> {
+    pub fn new(origin: Pos, width: usize, height: usize, fill: T) -> Result<Self, TooLarge>
+    where
+        T: Clone,
+    {
+        Ok(DenseGrid {
+            origin,
+            width,
+            height,
+            cells: vec![fill; cell_count(width, height)?],
+        })
+    }
+
+    /// A grid of the given rows, which must all be the same length.
+    pub fn from_rows(origin: Pos, rows: Vec<Vec<T>>) -> Self {
+        let height = rows.len();
+        let width = rows.first().map_or(0, |r| r.len());
+        assert!(rows.iter().all(|r| r.len() == width), "ragged rows");
+        DenseGrid {
+            origin,
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        }
+    }
+
+    /// The cells of a sparse grid within its bounding box, with `fill` where
+    /// it has none.
+    pub fn from_grid(grid: Grid<T>, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        DenseGrid::from_grid_with(grid, || fill.clone())
+    }
+
+    fn from_grid_with(grid: Grid<T>, mut fill: impl FnMut() -> T) -> Self {
+        let (min, max) = (grid.min_pos(), grid.max_pos());
+        let (width, height) = if grid.0.is_empty() {
+            (0, 0)
+        } else {
+            let span = |min: i32, max: i32| (max as i64 - min as i64 + 1) as usize;
+            (span(min.x, max.x), span(min.y, max.y))
+        };
+        // A grid spread too far to store densely couldn't be allocated anyway.
+        let cells = cell_count(width, height).unwrap_or_else(|e| panic!("{}", e));
+        let mut dense = DenseGrid {
+            origin: min,
+            width,
+            height,
+            cells: (0..cells).map(|_| fill()).collect(),
+        };
+        for (p, v) in grid.0 {
+            dense[p] = v;
+        }
+        dense
+    }
+
+    /// The cells for which `keep` holds, as a sparse grid.
+    pub fn into_grid(self, keep: impl Fn(&T) -> bool) -> Grid<T> {
+        let positions: Vec<Pos> = self.positions().collect();
+        Grid(
+            positions
+                .into_iter()
+                .zip(self.cells)
+                .filter(|(_, v)| keep(v))
+                .collect(),
+        )
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The top-left cell.
+    pub fn min_pos(&self) -> Pos {
+        self.origin
+    }
+
+    /// The bottom-right cell.
+    pub fn max_pos(&self) -> Pos {
+        Pos::new(
+            self.origin.x + self.width as i32 - 1,
+            self.origin.y + self.height as i32 - 1,
+        )
+    }
+
+    fn index_of(&self, p: &Pos) -> Option<usize> {
+        let x = p.x - self.origin.x;
+        let y = p.y - self.origin.y;
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            None
+        } else {
+            Some(y as usize * self.width + x as usize)
+        }
+    }
+
+    fn pos_of(&self, i: usize) -> Pos {
+        Pos::new(
+            self.origin.x + (i % self.width) as i32,
+            self.origin.y + (i / self.width) as i32,
+        )
+    }
+
+    pub fn contains(&self, p: &Pos) -> bool {
+        self.index_of(p).is_some()
+    }
+
+    pub fn get(&self, p: &Pos) -> Option<&T> {
+        self.index_of(p).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, p: &Pos) -> Option<&mut T> {
+        self.index_of(p).map(move |i| &mut self.cells[i])
+    }
+
+    /// Every position in the grid, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> + '_ {
+        (0..self.cells.len()).map(move |i| self.pos_of(i))
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` panics on 0, and an empty grid has no rows anyway.
+        self.cells.chunks(std::cmp::max(self.width, 1))
+    }
+
+    /// The cells of the `i`th column from the left, top to bottom.
+    pub fn column(&self, i: usize) -> impl Iterator<Item = &T> {
+        assert!(i < self.width, "column {} of {}", i, self.width);
+        self.cells.iter().skip(i).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |i| self.column(i))
+    }
+}
+
+impl<T> From<Grid<T>> for DenseGrid<Option<T>> {
+    fn from(grid: Grid<T>) -> Self {
+        let grid = Grid(grid.0.into_iter().map(|(p, v)| (p, Some(v))).collect());
+        DenseGrid::from_grid_with(grid, || None)
+    }
+}
+
+impl<T> From<DenseGrid<Option<T>>> for Grid<T> {
+    fn from(dense: DenseGrid<Option<T>>) -> Self {
+        let positions: Vec<Pos> = dense.positions().collect();
+        Grid(
+            positions
+                .into_iter()
+                .zip(dense.cells)
+                .filter_map(|(p, v)| v.map(|v| (p, v)))
+                .collect(),
+        )
+    }
+}
+
+impl<T> std::ops::Index<Pos> for DenseGrid<T> {
+    type Output = T;
+
+    fn index(&self, p: Pos) -> &T {
+        self.get(&p)
+            .unwrap_or_else(|| panic!("{} is outside the grid", p))
+    }
+}
+
+impl<T> std::ops::IndexMut<Pos> for DenseGrid<T> {
+    fn index_mut(&mut self, p: Pos) -> &mut T {
+        self.get_mut(&p)
+            .unwrap_or_else(|| panic!("{} is outside the grid", p))
+    }
+}
+
+impl<T> std::fmt::Display for DenseGrid<T>
+where
+    T: std::fmt::Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for row in self.rows() {
+            for v in row {
+                write!(f, "{}", v)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Which cells count as neighbours when searching a grid.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Adjacency {
+    /// Up, down, left and right.
+    Four,
+    /// Diagonals too.
+    Eight,
+}
+
+impl Adjacency {
+    pub fn neighbours(self, p: Pos) -> impl Iterator<Item = Pos> {
+        const FOUR: &[(i32, i32)] = &[(0, -1), (1, 0), (0, 1), (-1, 0)];
+        const EIGHT: &[(i32, i32)] = &[
+            (0, -1),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+            (0, 1),
+            (-1, 1),
+            (-1, 0),
+            (-1, -1),
+        ];
+        let offsets = match self {
+            Adjacency::Four => FOUR,
+            Adjacency::Eight => EIGHT,
+        };
+        offsets
+            .iter()
+            .map(move |(dx, dy)| Pos::new(p.x + dx, p.y + dy))
+    }
+}
+
+/// The shortest distances from a start position, and the way back from each
+/// position reached.
+#[derive(Debug)]
+pub struct Paths {
+    pub start: Pos,
+    pub dist: HashMap<Pos, u32>,
+    prev: HashMap<Pos, Pos>,
+}
+
+impl Paths {
+    fn new(start: Pos) -> Self {
+        let mut dist = HashMap::new();
+        dist.insert(start, 0);
+        Paths {
+            start,
+            dist,
+            prev: HashMap::new(),
+        }
+    }
+
+    pub fn distance(&self, to: &Pos) -> Option<u32> {
+        self.dist.get(to).copied()
+    }
+
+    /// The positions from the start to `to`, inclusive.
+    pub fn path_to(&self, to: &Pos) -> Option<Vec<Pos>> {
+        self.dist.get(to)?;
+        let mut path = vec![*to];
+        let mut p = to;
+        while let Some(prev) = self.prev.get(p) {
+            path.push(*prev);
+            p = prev;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// A bounding box, worked out once for a search rather than for every
+/// position it visits.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bounds {
+    pub min: Pos,
+    pub max: Pos,
+}
+
+impl Bounds {
+    pub fn contains(&self, p: &Pos) -> bool {
+        self.min.x <= p.x && p.x <= self.max.x && self.min.y <= p.y && p.y <= self.max.y
+    }
+}
+
+impl<T> Grid<T> {
+    /// The bounding box, which contains nothing if the grid is empty.
+    pub fn bounds(&self) -> Bounds {
+        let mut keys = self.0.keys();
+        let first = match keys.next() {
+            Some(&first) => first,
+            None => {
+                return Bounds {
+                    min: Pos::new(0, 0),
+                    max: Pos::new(-1, -1),
+                }
+            }
+        };
+        keys.fold(
+            Bounds {
+                min: first,
+                max: first,
+            },
+            |b, p| Bounds {
+                min: Pos::new(b.min.x.min(p.x), b.min.y.min(p.y)),
+                max: Pos::new(b.max.x.max(p.x), b.max.y.max(p.y)),
+            },
+        )
+    }
+}
+
+/// Searches only visit positions within the grid's bounding box, whether or
+/// not they hold a value, so that they end.
+impl<T> Grid<T> {
+    /// Breadth-first search from `start` over the positions for which
+    /// `passable` holds.
+    pub fn bfs(
+        &self,
+        start: Pos,
+        adjacency: Adjacency,
+        passable: impl Fn(&Pos, Option<&T>) -> bool,
+    ) -> Paths {
+        self.bfs_within(self.bounds(), start, adjacency, passable)
+    }
+
+    fn bfs_within(
+        &self,
+        bounds: Bounds,
+        start: Pos,
+        adjacency: Adjacency,
+        passable: impl Fn(&Pos, Option<&T>) -> bool,
+    ) -> Paths {
+        let mut paths = Paths::new(start);
+        let mut queue = VecDeque::new();
+        queue.push_back(start);
+        while let Some(p) = queue.pop_front() {
+            let d = paths.dist[&p];
+            for n in adjacency.neighbours(p) {
+                if paths.dist.contains_key(&n)
+                    || !bounds.contains(&n)
+                    || !passable(&n, self.0.get(&n))
+                {
+                    continue;
                 }
+                paths.dist.insert(n, d + 1);
+                paths.prev.insert(n, p);
+                queue.push_back(n);
             }
         }
-        Ok(Grid(roids))
+        paths
+    }
+
+    /// Shortest paths from `start`, where `cost` gives the cost of moving
+    /// onto a position, or `None` if it can't be entered.
+    pub fn dijkstra(
+        &self,
+        start: Pos,
+        adjacency: Adjacency,
+        cost: impl Fn(&Pos, Option<&T>) -> Option<u32>,
+    ) -> Paths {
+        self.search(start, None, adjacency, cost, |_| 0)
+    }
+
+    /// The cost and path of the cheapest way from `start` to `goal`, guided
+    /// by `heuristic`, which must never overestimate the remaining cost.
+    pub fn a_star(
+        &self,
+        start: Pos,
+        goal: Pos,
+        adjacency: Adjacency,
+        cost: impl Fn(&Pos, Option<&T>) -> Option<u32>,
+        heuristic: impl Fn(&Pos) -> u32,
+    ) -> Option<(u32, Vec<Pos>)> {
+        let paths = self.search(start, Some(goal), adjacency, cost, heuristic);
+        Some((paths.distance(&goal)?, paths.path_to(&goal)?))
+    }
+
+    fn search(
+        &self,
+        start: Pos,
+        goal: Option<Pos>,
+        adjacency: Adjacency,
+        cost: impl Fn(&Pos, Option<&T>) -> Option<u32>,
+        heuristic: impl Fn(&Pos) -> u32,
+    ) -> Paths {
+        let bounds = self.bounds();
+        let mut paths = Paths::new(start);
+        let mut queue = BinaryHeap::new();
+        queue.push(Reverse((heuristic(&start), 0, start)));
+        while let Some(Reverse((_, d, p))) = queue.pop() {
+            if Some(p) == goal {
+                break;
+            }
+            if d > paths.dist[&p] {
+                continue;
+            }
+            for n in adjacency.neighbours(p) {
+                if !bounds.contains(&n) {
+                    continue;
+                }
+                let step = match cost(&n, self.0.get(&n)) {
+                    Some(step) => step,
+                    None => continue,
+                };
+                let dn = d + step;
+                if paths.dist.get(&n).is_none_or(|&old| dn < old) {
+                    paths.dist.insert(n, dn);
+                    paths.prev.insert(n, p);
+                    queue.push(Reverse((dn + heuristic(&n), dn, n)));
+                }
+            }
+        }
+        paths
+    }
+}
+
+/// A connected set of positions.
+#[derive(Debug, PartialEq)]
+pub struct Region {
+    pub cells: HashSet<Pos>,
+}
+
+impl Region {
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// The number of cell edges that don't border another cell of the region.
+    pub fn perimeter(&self) -> usize {
+        self.cells
+            .iter()
+            .flat_map(|&p| Adjacency::Four.neighbours(p))
+            .filter(|n| !self.cells.contains(n))
+            .count()
+    }
+
+    /// The top-left and bottom-right corners of the region.
+    pub fn bounding_box(&self) -> (Pos, Pos) {
+        let xs = || self.cells.iter().map(|p| p.x);
+        let ys = || self.cells.iter().map(|p| p.y);
+        (
+            Pos::new(xs().min().unwrap_or(0), ys().min().unwrap_or(0)),
+            Pos::new(xs().max().unwrap_or(0), ys().max().unwrap_or(0)),
+        )
+    }
+}
+
+/// Like searches, fills stay within the grid's bounding box.
+impl<T> Grid<T> {
+    /// The positions reachable from `start` through positions for which
+    /// `inside` holds, including `start` if it does.
+    pub fn flood_fill(
+        &self,
+        start: Pos,
+        adjacency: Adjacency,
+        inside: impl Fn(&Pos, Option<&T>) -> bool,
+    ) -> HashSet<Pos> {
+        self.fill_within(self.bounds(), start, adjacency, inside)
+            .map_or_else(HashSet::new, |paths| paths.dist.into_keys().collect())
+    }
+
+    /// The paths filling the region containing `start`, or `None` if `start`
+    /// isn't inside.
+    fn fill_within(
+        &self,
+        bounds: Bounds,
+        start: Pos,
+        adjacency: Adjacency,
+        inside: impl Fn(&Pos, Option<&T>) -> bool,
+    ) -> Option<Paths> {
+        if !bounds.contains(&start) || !inside(&start, self.0.get(&start)) {
+            return None;
+        }
+        Some(self.bfs_within(bounds, start, adjacency, inside))
+    }
+
+    /// How many steps it takes to fill the region containing `start`, or
+    /// `None` if `start` isn't inside.
+    pub fn fill_time(
+        &self,
+        start: Pos,
+        adjacency: Adjacency,
+        inside: impl Fn(&Pos, Option<&T>) -> bool,
+    ) -> Option<u32> {
+        self.fill_within(self.bounds(), start, adjacency, inside)?
+            .dist
+            .into_values()
+            .max()
+    }
+
+    /// The connected regions of positions for which `inside` holds, in the
+    /// order of their first cell, row by row.
+    pub fn components(
+        &self,
+        adjacency: Adjacency,
+        inside: impl Fn(&Pos, Option<&T>) -> bool,
+    ) -> Vec<Region> {
+        let mut regions = vec![];
+        let bounds = self.bounds();
+        let mut seen = HashSet::new();
+        for y in bounds.min.y..=bounds.max.y {
+            for x in bounds.min.x..=bounds.max.x {
+                let p = Pos::new(x, y);
+                if seen.contains(&p) {
+                    continue;
+                }
+                if let Some(paths) = self.fill_within(bounds, p, adjacency, &inside) {
+                    let cells: HashSet<Pos> = paths.dist.into_keys().collect();
+                    seen.extend(cells.iter().copied());
+                    regions.push(Region { cells });
+                }
+            }
+        }
+        regions
+    }
+
+    /// Each position inside a region, labelled with the region's index in
+    /// `components`.
+    pub fn label_components(
+        &self,
+        adjacency: Adjacency,
+        inside: impl Fn(&Pos, Option<&T>) -> bool,
+    ) -> Grid<usize> {
+        let regions = self.components(adjacency, inside);
+        Grid(
+            regions
+                .into_iter()
+                .enumerate()
+                .flat_map(|(i, r)| r.cells.into_iter().map(move |p| (p, i)))
+                .collect(),
+        )
+    }
+}
+
+/// Transforms keep the grid's bounding box where it was: rotating and
+/// flipping move cells within it, rather than about the origin.
+impl<T> Grid<T> {
+    fn map_positions(self, f: impl Fn(Pos) -> Pos) -> Grid<T> {
+        Grid(self.0.into_iter().map(|(p, v)| (f(p), v)).collect())
+    }
+
+    /// Rotate a quarter turn clockwise.
+    pub fn rotate_right(self) -> Grid<T> {
+        let (min, max) = (self.min_pos(), self.max_pos());
+        self.map_positions(|p| Pos::new(min.x + max.y - p.y, min.y + p.x - min.x))
+    }
+
+    /// Rotate a quarter turn anticlockwise.
+    pub fn rotate_left(self) -> Grid<T> {
+        let (min, max) = (self.min_pos(), self.max_pos());
+        self.map_positions(|p| Pos::new(min.x + p.y - min.y, min.y + max.x - p.x))
+    }
+
+    /// Mirror left to right.
+    pub fn flip_horizontal(self) -> Grid<T> {
+        let (min, max) = (self.min_pos(), self.max_pos());
+        self.map_positions(|p| Pos::new(min.x + max.x - p.x, p.y))
+    }
+
+    /// Mirror top to bottom.
+    pub fn flip_vertical(self) -> Grid<T> {
+        let (min, max) = (self.min_pos(), self.max_pos());
+        self.map_positions(|p| Pos::new(p.x, min.y + max.y - p.y))
+    }
+
+    pub fn translate(self, dx: i32, dy: i32) -> Grid<T> {
+        self.map_positions(|p| Pos::new(p.x + dx, p.y + dy))
+    }
+
+    /// Move the grid so its bounding box starts at `0,0`.
+    pub fn normalize(self) -> Grid<T> {
+        let min = self.min_pos();
+        self.translate(-min.x, -min.y)
+    }
+
+    /// Keep only the cells between `min` and `max` inclusive.
+    pub fn crop(self, min: Pos, max: Pos) -> Grid<T> {
+        let inside = |p: &Pos| min.x <= p.x && p.x <= max.x && min.y <= p.y && p.y <= max.y;
+        Grid(self.0.into_iter().filter(|(p, _)| inside(p)).collect())
+    }
+
+    /// A copy of the cells between `min` and `max` inclusive, moved so that
+    /// `min` is at `0,0`.
+    pub fn extract(&self, min: Pos, max: Pos) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let inside = |p: &Pos| min.x <= p.x && p.x <= max.x && min.y <= p.y && p.y <= max.y;
+        Grid(
+            self.0
+                .iter()
+                .filter(|(p, _)| inside(p))
+                .map(|(p, v)| (Pos::new(p.x - min.x, p.y - min.y), v.clone()))
+                .collect(),
+        )
+    }
+
+    /// Copy the cells of `other` into this grid, with `other`'s `0,0` at
+    /// `at`. Cells `other` has no value for are left alone.
+    pub fn paste(&mut self, other: &Grid<T>, at: Pos)
+    where
+        T: Clone,
+    {
+        for (p, v) in other.0.iter() {
+            self.0.insert(Pos::new(at.x + p.x, at.y + p.y), v.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dense() {
+        let mut grid: Grid<char> = "#..\n.##\n".parse().unwrap();
+        grid.0.insert(Pos::new(-1, 2), 'x');
+        let dense = DenseGrid::from_grid(grid, ' ');
+        assert_eq!((dense.width(), dense.height()), (4, 3));
+        assert_eq!(dense.min_pos(), Pos::new(-1, 0));
+        assert_eq!(dense.max_pos(), Pos::new(2, 2));
+        assert_eq!(dense[Pos::new(0, 0)], '#');
+        assert_eq!(dense.get(&Pos::new(3, 0)), None);
+        assert_eq!(dense.to_string(), " #  \n  ##\nx   \n");
+        let column: String = dense.column(2).collect();
+        assert_eq!(column, " # ");
+        assert_eq!(dense.columns().count(), 4);
+        assert_eq!(dense.rows().nth(1), Some(&[' ', ' ', '#', '#'][..]));
+
+        let grid = dense.into_grid(|&c| c != ' ');
+        assert_eq!(grid.0.len(), 4);
+        assert_eq!(grid.0.get(&Pos::new(-1, 2)), Some(&'x'));
+
+        let dense = DenseGrid::new(Pos::new(1, 1), 3, 2, '.').unwrap();
+        assert_eq!(dense.to_string(), "...\n...\n");
+        assert_eq!(dense.min_pos(), Pos::new(1, 1));
+        assert_eq!(
+            DenseGrid::new(Pos::new(0, 0), usize::MAX, 2, '.'),
+            Err(TooLarge {
+                width: usize::MAX,
+                height: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_dense_round_trip() {
+        let grid: Grid<char> = "..#\n#..\n".parse().unwrap();
+        let dense: DenseGrid<Option<char>> = DenseGrid::from(Grid(grid.0.clone()));
+        assert_eq!(dense.get(&Pos::new(1, 0)), Some(&None));
+        assert_eq!(Grid::from(dense), grid);
+        let empty: DenseGrid<Option<char>> = Grid::new().into();
+        assert_eq!(empty.rows().count(), 0);
+    }
+
+    const MAZE: &str = "\
+#######
+#..#..#
+#.##.##
+#.....#
+#######
+";
+
+    #[test]
+    fn test_bfs() {
+        let maze: Grid<char> = MAZE.parse().unwrap();
+        let open = |_: &Pos, c: Option<&char>| c.is_none();
+        let paths = maze.bfs(Pos::new(1, 1), Adjacency::Four, open);
+        assert_eq!(paths.distance(&Pos::new(5, 1)), Some(8));
+        assert_eq!(paths.distance(&Pos::new(0, 0)), None);
+        assert_eq!(
+            paths.path_to(&Pos::new(2, 1)),
+            Some(vec![Pos::new(1, 1), Pos::new(2, 1)])
+        );
+        let paths = maze.bfs(Pos::new(1, 1), Adjacency::Eight, open);
+        assert_eq!(paths.distance(&Pos::new(5, 1)), Some(5));
+
+        let bounds = maze.bounds();
+        assert_eq!((bounds.min, bounds.max), (maze.min_pos(), maze.max_pos()));
+        let empty = Grid::<char>::new();
+        assert!(!empty.bounds().contains(&Pos::new(0, 0)));
+        assert_eq!(
+            empty.bfs(Pos::new(0, 0), Adjacency::Four, open).dist.len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_dijkstra_and_a_star() {
+        let maze: Grid<char> = MAZE.parse().unwrap();
+        // Walking along row 3 costs extra.
+        let cost = |p: &Pos, c: Option<&char>| match c {
+            Some(_) => None,
+            None if p.y == 3 => Some(2),
+            None => Some(1),
+        };
+        let (start, goal) = (Pos::new(1, 1), Pos::new(4, 1));
+        let paths = maze.dijkstra(start, Adjacency::Four, cost);
+        assert_eq!(paths.distance(&goal), Some(11));
+        let (d, path) = maze
+            .a_star(start, goal, Adjacency::Four, cost, |p| {
+                p.dist_to(&goal) as u32
+            })
+            .unwrap();
+        assert_eq!(d, 11);
+        assert_eq!(path.len(), 8);
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&goal));
+        assert_eq!(
+            maze.a_star(start, Pos::new(0, 0), Adjacency::Four, cost, |_| 0),
+            None
+        );
+    }
+
+    #[test]
+    fn test_direction() {
+        use Direction::*;
+        assert_eq!(North.turn_right(), East);
+        assert_eq!(North.turn_left(), West);
+        assert_eq!(West.turn(Turn::Right), North);
+        assert_eq!(East.reverse(), West);
+        assert_eq!("U".parse(), Ok(North));
+        assert_eq!("W".parse(), Ok(West));
+        assert_eq!(
+            "X".parse::<Direction>(),
+            Err(DirectionParseError("X".to_string()))
+        );
+        assert_eq!(Pos::new(1, 1) + North, Pos::new(1, 0));
+        assert_eq!("3,-4".parse(), Ok(Pos::new(3, -4)));
+        assert_eq!(Pos::new(-1, 2).to_string().parse(), Ok(Pos::new(-1, 2)));
+        assert_eq!("3".parse::<Pos>(), Err(PosParseError("3".to_string())));
+        assert!("1,2,3".parse::<Pos>().is_err());
+        assert_eq!(Pos::new(1, 1).step(West, 3), Pos::new(-2, 1));
+    }
+
+    #[test]
+    fn test_render() {
+        let grid: Grid<char> = "#.\n.#\n".parse().unwrap();
+        assert_eq!(grid.to_string(), "#.\n.#\n");
+        assert_eq!(grid.render().empty(' ').flip_y().to_string(), " #\n# \n");
+        assert_eq!(
+            grid.render()
+                .viewport(Pos::new(-1, 0), Pos::new(10, 1))
+                .axes()
+                .legend('#', "wall")
+                .to_string(),
+            "  -          1
+  101234567890
+0 .#..........
+1 ..#.........
+
+# wall
+"
+        );
+    }
+
+    #[test]
+    fn test_parser() {
+        let map = "#.a\n.█#\n";
+        let grid: Grid<char> = Parser::new().keep_all().parse(map).unwrap();
+        assert_eq!(grid.0.len(), 6);
+        assert_eq!(grid.0.get(&Pos::new(1, 1)), Some(&'█'));
+
+        let walls = Parser::new()
+            .empty('#')
+            .origin(Pos::new(-1, -1))
+            .parse_with(map, |c| match c {
+                '.' => Ok(false),
+                c if c.is_alphabetic() || c == '█' => Ok(true),
+                c => Err(c),
+            })
+            .unwrap();
+        assert_eq!(walls.0.get(&Pos::new(-1, -1)), None);
+        assert_eq!(walls.0.get(&Pos::new(0, -1)), Some(&false));
+        assert_eq!(walls.0.get(&Pos::new(0, 0)), Some(&true));
+
+        let err = map.parse::<Grid<u8>>().unwrap_err();
+        assert_eq!((err.line, err.column), (1, 1));
+        assert!(err
+            .to_string()
+            .starts_with("Couldn't parse grid at line 1, column 1: "));
+    }
+
+    #[test]
+    fn test_regions() {
+        let map: Grid<char> = "##.#\n#..#\n.#.#\n".parse().unwrap();
+        let wall = |_: &Pos, c: Option<&char>| c.is_some();
+        let regions = map.components(Adjacency::Four, wall);
+        assert_eq!(
+            regions.iter().map(|r| r.area()).collect::<Vec<_>>(),
+            vec![3, 3, 1]
+        );
+        assert_eq!(regions[0].perimeter(), 8);
+        assert_eq!(regions[1].bounding_box(), (Pos::new(3, 0), Pos::new(3, 2)));
+        assert_eq!(map.components(Adjacency::Eight, wall).len(), 2);
+        assert_eq!(
+            map.label_components(Adjacency::Four, wall).to_string(),
+            "00.1\n0..1\n.2.1\n"
+        );
+
+        let open = |_: &Pos, c: Option<&char>| c.is_none();
+        assert_eq!(
+            map.flood_fill(Pos::new(2, 0), Adjacency::Four, open).len(),
+            4
+        );
+        assert!(map
+            .flood_fill(Pos::new(0, 0), Adjacency::Four, open)
+            .is_empty());
+        assert_eq!(
+            map.fill_time(Pos::new(2, 0), Adjacency::Four, open),
+            Some(2)
+        );
+        assert_eq!(map.fill_time(Pos::new(0, 0), Adjacency::Four, open), None);
+    }
+
+    #[test]
+    fn test_transforms() {
+        let grid = || -> Grid<char> {
+            Parser::new()
+                .origin(Pos::new(-1, -1))
+                .parse("ab.\n..c\n")
+                .unwrap()
+        };
+        assert_eq!(grid().rotate_right().to_string(), ".a\n.b\nc.\n");
+        assert_eq!(grid().rotate_left().to_string(), ".c\nb.\na.\n");
+        assert_eq!(grid().rotate_right().min_pos(), Pos::new(-1, -1));
+        assert_eq!(
+            grid()
+                .rotate_right()
+                .rotate_right()
+                .rotate_left()
+                .rotate_left(),
+            grid()
+        );
+        assert_eq!(grid().flip_horizontal().to_string(), ".ba\nc..\n");
+        assert_eq!(grid().flip_vertical().to_string(), "..c\nab.\n");
+        assert_eq!(grid().normalize().min_pos(), Pos::new(0, 0));
+        assert_eq!(grid().translate(3, 1).max_pos(), Pos::new(4, 1));
+        assert_eq!(
+            grid().crop(Pos::new(0, -1), Pos::new(1, 0)).to_string(),
+            "b.\n.c\n"
+        );
+
+        let part = grid().extract(Pos::new(0, -1), Pos::new(1, 0));
+        assert_eq!(part.min_pos(), Pos::new(0, 0));
+        let mut pasted = grid();
+        pasted.paste(&part, Pos::new(-1, 0));
+        assert_eq!(pasted.to_string(), "ab.\nb.c\n.c.\n");
+    }
+
+    #[test]
+    fn test_ray() {
+        let from = Pos::new(1, 1);
+        assert_eq!(Ray::between(&from, &Pos::new(3, 5)), Ray::new(1, 2));
+        assert_eq!(Ray::new(-4, 0), Ray::new(-1, 0));
+        let ray = Ray::new(-4, 6).unwrap();
+        assert_eq!((ray.dx(), ray.dy()), (-2, 3));
+        assert_eq!(Ray::new(0, -3), Some(Ray::UP));
+        assert_eq!(Ray::new(0, 0), None);
+        assert_eq!(Ray::between(&from, &from), None);
+
+        // Clockwise from straight up.
+        let around = [
+            (0, -1),
+            (1, -2),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+            (0, 1),
+            (-1, 1),
+            (-1, 0),
+            (-2, -1),
+        ];
+        let rays: Vec<Ray> = around
+            .iter()
+            .map(|&(dx, dy)| Ray::new(dx, dy).unwrap())
+            .collect();
+        let mut sorted = rays.clone();
+        sorted.reverse();
+        sorted.sort();
+        assert_eq!(sorted, rays);
+        // Directions too close for `f32` angles to tell apart.
+        assert!(Ray::new(1, -100_000) < Ray::new(1, -99_999));
+        assert_eq!(
+            Ray::new(3, 6).cmp(&Ray::new(1, 2)),
+            std::cmp::Ordering::Equal
+        );
     }
 }
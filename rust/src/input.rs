@@ -0,0 +1,199 @@
+//! Finding and reading puzzle inputs.
+//!
+//! Inputs are named after their day, `day_07`, optionally followed by a name
+//! for alternative inputs such as examples, `day_07.example1`, and are read
+//! from `<name>.txt` in the data directory.
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Environment variable naming the data directory.
+pub const DATA_DIR_ENV: &str = "AOC_DATA_DIR";
+
+/// Where the inputs live when this crate is run from its own directory, or
+/// from anywhere when run from the checkout it was built in.
+const DEFAULT_DATA_DIRS: &[&str] = &[
+    "../ocaml/data",
+    concat!(env!("CARGO_MANIFEST_DIR"), "/../ocaml/data"),
+];
+
+#[derive(Debug)]
+pub enum Error {
+    NotFound { input: String, tried: Vec<PathBuf> },
+    Io { path: PathBuf, err: std::io::Error },
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::NotFound { input, tried } => {
+                write!(f, "couldn't find input {}, tried:", input)?;
+                for path in tried {
+                    write!(f, "\n  {}", path.display())?;
+                }
+                Ok(())
+            }
+            Error::Io { path, err } => write!(f, "couldn't read {}: {}", path.display(), err),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// The name of a day's input, e.g. `day_07` or `day_07.example1`.
+pub fn name(day: u8, variant: Option<&str>) -> String {
+    match variant {
+        Some(variant) => format!("day_{:02}.{}", day, variant),
+        None => format!("day_{:02}", day),
+    }
+}
+
+/// The day an input belongs to, from its `day_NN` prefix.
+pub fn day_of(input: &str) -> Option<u8> {
+    let rest = input.strip_prefix("day_")?;
+    let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
+
+/// Read a file, or standard input if `path` is `-`.
+pub fn read_file(path: &str) -> Result<String, Error> {
+    let mut data = String::new();
+    let result = if path == "-" {
+        std::io::stdin().read_to_string(&mut data).map(|_| data)
+    } else {
+        std::fs::read_to_string(path)
+    };
+    result.map_err(|err| Error::Io {
+        path: PathBuf::from(path),
+        err,
+    })
+}
+
+/// The directories searched for inputs.
+pub struct DataDirs(pub Vec<PathBuf>);
+
+impl DataDirs {
+    /// Use `dir` if given, otherwise the directory named by `AOC_DATA_DIR`,
+    /// otherwise the default locations.
+    pub fn new(dir: Option<&str>) -> Self {
+        let dirs = match dir.map(String::from).or_else(|| std::env::var(DATA_DIR_ENV).ok()) {
+            Some(dir) => vec![PathBuf::from(dir)],
+            None => DEFAULT_DATA_DIRS.iter().map(PathBuf::from).collect(),
+        };
+        DataDirs(dirs)
+    }
+
+    pub fn find(&self, input: &str) -> Result<PathBuf, Error> {
+        let tried: Vec<PathBuf> = self
+            .0
+            .iter()
+            .map(|dir| dir.join(format!("{}.txt", input)))
+            .collect();
+        match tried.iter().find(|path| path.is_file()) {
+            Some(path) => Ok(path.clone()),
+            None => Err(Error::NotFound {
+                input: input.to_string(),
+                tried,
+            }),
+        }
+    }
+
+    pub fn read(&self, input: &str) -> Result<String, Error> {
+        let path = self.find(input)?;
+        std::fs::read_to_string(&path).map_err(|err| Error::Io { path, err })
+    }
+
+    /// Every input for `day` in the first data directory that has any,
+    /// with the plain `day_NN` input first.
+    pub fn discover(&self, day: u8) -> Vec<String> {
+        let prefix = name(day, None);
+        for dir in self.0.iter() {
+            let mut inputs: Vec<String> = list_inputs(dir)
+                .into_iter()
+                .filter(|input| {
+                    input == &prefix || input.starts_with(&format!("{}.", prefix))
+                })
+                .collect();
+            if !inputs.is_empty() {
+                inputs.sort_by_key(|input| (input != &prefix, input.clone()));
+                return inputs;
+            }
+        }
+        vec![]
+    }
+}
+
+fn list_inputs(dir: &Path) -> Vec<String> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let file_name = entry.file_name();
+            let file_name = file_name.to_str()?;
+            file_name.strip_suffix(".txt").map(String::from)
+        })
+        .filter(|input| day_of(input).is_some())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data_dir(name: &str, files: &[&str]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc-2019-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for file in files {
+            std::fs::write(dir.join(file), "1").unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_name() {
+        assert_eq!(name(7, None), "day_07");
+        assert_eq!(name(7, Some("example1")), "day_07.example1");
+        assert_eq!(day_of("day_07.example1"), Some(7));
+        assert_eq!(day_of("answers"), None);
+    }
+
+    #[test]
+    fn test_find() {
+        let dir = data_dir("find", &["day_01.txt"]);
+        let dirs = DataDirs(vec![PathBuf::from("/nonexistent"), dir.clone()]);
+        assert_eq!(dirs.find("day_01").unwrap(), dir.join("day_01.txt"));
+        match dirs.find("day_02") {
+            Err(Error::NotFound { tried, .. }) => assert_eq!(
+                tried,
+                vec![
+                    PathBuf::from("/nonexistent/day_02.txt"),
+                    dir.join("day_02.txt")
+                ]
+            ),
+            r => panic!("expected NotFound, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn test_discover() {
+        let dir = data_dir(
+            "discover",
+            &[
+                "day_07.example2.txt",
+                "day_07.txt",
+                "day_07.example1.txt",
+                "day_08.txt",
+                "notes.txt",
+            ],
+        );
+        let dirs = DataDirs(vec![dir]);
+        assert_eq!(
+            dirs.discover(7),
+            vec!["day_07", "day_07.example1", "day_07.example2"]
+        );
+        assert_eq!(dirs.discover(9), Vec::<String>::new());
+    }
+}
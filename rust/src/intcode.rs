@@ -1,3 +1,8 @@
+/// Parse a comma-separated Intcode program.
+pub fn parse(s: &str) -> Result<Vec<i64>, std::num::ParseIntError> {
+    s.trim().split(',').map(|s| s.parse::<i64>()).collect()
+}
+
 #[derive(Clone)]
 enum Mode {
     Position,
@@ -24,6 +29,13 @@ pub enum State {
     Terminated,
 }
 
+/// Number of times a memory address has been read from and written to.
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub struct Access {
+    pub reads: usize,
+    pub writes: usize,
+}
+
 #[derive(Clone)]
 pub struct Program {
     pc: usize,
@@ -31,6 +43,7 @@ pub struct Program {
     pub state: State,
     relative_base: usize,
     param_modes: Vec<Mode>,
+    access: Option<Vec<Access>>,
 }
 
 impl Program {
@@ -41,6 +54,26 @@ impl Program {
             state: State::Running,
             relative_base: 0,
             param_modes: vec![],
+            access: None,
+        }
+    }
+
+    /// Start counting reads and writes to each memory address.
+    pub fn trace_access(&mut self) {
+        self.access = Some(vec![Access::default(); self.memory.len()]);
+    }
+
+    /// Reads and writes per address since `trace_access` was called.
+    pub fn access(&self) -> Option<&[Access]> {
+        self.access.as_deref()
+    }
+
+    fn record_access(&mut self, addr: usize, f: impl Fn(&mut Access)) {
+        if let Some(access) = self.access.as_mut() {
+            if addr >= access.len() {
+                access.resize(addr + 1, Access::default())
+            }
+            f(&mut access[addr])
         }
     }
 
@@ -49,6 +82,7 @@ impl Program {
             self.memory.resize(addr + 1, 0)
         }
 
+        self.record_access(addr, |a| a.reads += 1);
         *self.memory.get(addr).expect("invalid pc")
     }
 
@@ -57,6 +91,7 @@ impl Program {
             self.memory.resize(addr + 1, 0)
         }
 
+        self.record_access(addr, |a| a.writes += 1);
         self.memory[addr] = val
     }
 
@@ -90,17 +125,17 @@ impl Program {
         addr
     }
 
-    fn op_3<'a, F>(&mut self, f: F)
+    fn op_3<F>(&mut self, f: F)
     where
         F: Fn(i64, i64) -> i64,
     {
         let arg_1 = self.next_param_value();
         let arg_2 = self.next_param_value();
         let result_addr = self.next_param_addr();
-        self.set(result_addr as usize, f(arg_1, arg_2));
+        self.set(result_addr, f(arg_1, arg_2));
     }
 
-    fn jump_if<'a, F>(&mut self, f: F)
+    fn jump_if<F>(&mut self, f: F)
     where
         F: Fn(i64) -> bool,
     {
@@ -142,7 +177,7 @@ impl Program {
             // store input
             {
                 let addr = self.next_param_addr();
-                self.state = State::WaitForInput(addr as usize);
+                self.state = State::WaitForInput(addr);
             }
             Ok(4) =>
             // output
@@ -181,11 +216,21 @@ impl Program {
     }
 
     pub fn run(&mut self, input: &mut impl Iterator<Item = i64>) -> Vec<i64> {
+        self.run_for(input, usize::MAX)
+    }
+
+    /// Like `run`, but stop after executing at most `steps` instructions.
+    pub fn run_for(&mut self, input: &mut impl Iterator<Item = i64>, steps: usize) -> Vec<i64> {
         let mut output = Vec::new();
+        let mut steps_left = steps;
         loop {
             match self.state {
                 State::Terminated => return output,
-                State::Running => self.step(),
+                State::Running if steps_left == 0 => return output,
+                State::Running => {
+                    steps_left -= 1;
+                    self.step()
+                }
                 State::Output(o) => {
                     output.push(o);
                     self.state = State::Running;
@@ -414,4 +459,30 @@ mod tests {
             assert_eq!(output[0], mem[1]);
         }
     }
+
+    mod access {
+        use super::*;
+
+        #[test]
+        fn test_trace_access() {
+            let mut program = Program::new(vec![1, 5, 6, 7, 99, 30, 40, 0]);
+            program.trace_access();
+            let _ = program.run(&mut std::iter::empty());
+            let access = program.access().unwrap();
+            assert_eq!(access[0], Access { reads: 1, writes: 0 });
+            assert_eq!(access[5], Access { reads: 1, writes: 0 });
+            assert_eq!(access[7], Access { reads: 0, writes: 1 });
+        }
+
+        #[test]
+        fn test_run_for() {
+            let mut program = Program::new(vec![1101, 1, 1, 0, 1101, 2, 2, 0, 99]);
+            let _ = program.run_for(&mut std::iter::empty(), 1);
+            assert_eq!(program.memory[0], 2);
+            assert_eq!(program.state, State::Running);
+            let _ = program.run_for(&mut std::iter::empty(), 10);
+            assert_eq!(program.memory[0], 4);
+            assert_eq!(program.state, State::Terminated);
+        }
+    }
 }
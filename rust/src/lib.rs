@@ -0,0 +1,32 @@
+//! Solutions to Advent of Code 2019, and the tools shared between them.
+
+#[macro_use]
+extern crate itertools;
+
+pub mod animate;
+pub mod answers;
+pub mod bench;
+pub mod export;
+pub mod grid;
+pub mod input;
+pub mod intcode;
+pub mod memory;
+pub mod ocr;
+pub mod report;
+pub mod runner;
+pub mod scaffold;
+pub mod solution;
+
+pub mod day_01;
+pub mod day_02;
+pub mod day_03;
+pub mod day_04;
+pub mod day_05;
+pub mod day_06;
+pub mod day_07;
+pub mod day_08;
+pub mod day_09;
+pub mod day_10;
+pub mod day_11;
+pub mod day_12;
+pub mod day_13;
@@ -1,81 +1,714 @@
+extern crate aoc_2019;
 extern crate clap;
-#[macro_use]
-extern crate itertools;
+
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+
+use aoc_2019::solution::{self, DAYS, PARTS};
+use aoc_2019::grid::{Grid, Pos, Ray};
+use aoc_2019::{
+    animate, answers, bench, day_08, day_10, day_11, day_13, input, intcode, memory, report,
+    runner, scaffold,
+};
+
+fn is_number(s: String) -> Result<(), String> {
+    s.parse::<u64>()
+        .map(|_| ())
+        .map_err(|_| format!("expected a number, got '{}'", s))
+}
+
+fn is_positive(s: String) -> Result<(), String> {
+    match s.parse::<u64>() {
+        Ok(0) => Err("must be at least 1".to_string()),
+        _ => is_number(s),
+    }
+}
+
+fn is_pos(s: String) -> Result<(), String> {
+    s.parse::<Pos>().map(|_| ()).map_err(|e| e.to_string())
+}
+
+fn day_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("day")
+        .short("d")
+        .long("day")
+        .value_name("DD")
+        .help("Day to solve")
+        .takes_value(true)
+        .validator(is_number)
+}
+
+fn part_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("part")
+        .short("p")
+        .long("part")
+        .value_name("PART")
+        .help("Part to solve")
+        .takes_value(true)
+        .possible_values(&["1", "2"])
+}
+
+fn file_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("file")
+        .short("f")
+        .long("file")
+        .value_name("FILE")
+        .help("Data file, or - for standard input")
+        .takes_value(true)
+}
+
+fn input_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("input")
+        .short("i")
+        .long("input")
+        .value_name("NAME")
+        .help("Named input, e.g. example1 for day_07.example1.txt")
+        .takes_value(true)
+        .conflicts_with("file")
+}
+
+fn program_args<'a, 'b>(cmd: App<'a, 'b>) -> App<'a, 'b> {
+    cmd.arg(
+        Arg::with_name("program")
+            .value_name("FILE")
+            .help("Intcode program")
+            .required(true),
+    )
+    .arg(
+        Arg::with_name("set")
+            .short("s")
+            .long("set")
+            .value_name("ADDR=VAL")
+            .help("Set memory before running")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1),
+    )
+    .arg(
+        Arg::with_name("input")
+            .short("i")
+            .long("input")
+            .value_name("N,N,...")
+            .help("Input values, repeated as needed")
+            .takes_value(true)
+            .default_value("0"),
+    )
+    .arg(
+        Arg::with_name("steps")
+            .short("n")
+            .long("steps")
+            .value_name("N")
+            .help("Stop after N instructions")
+            .takes_value(true)
+            .validator(is_number),
+    )
+}
 
 fn main() {
-    let matches = clap::App::new("AoC 2019")
+    let matches = App::new("AoC 2019")
         .author("Matt Bray <mattjbray@gmail.com>")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
         .arg(
-            clap::Arg::with_name("day")
-                .short("d")
-                .long("day")
-                .value_name("DD")
-                .help("Day to solve")
+            Arg::with_name("data-dir")
+                .long("data-dir")
+                .value_name("DIR")
+                .help("Directory containing the inputs [env: AOC_DATA_DIR]")
                 .takes_value(true)
-                .default_value("01"),
+                .global(true),
         )
         .arg(
-            clap::Arg::with_name("part")
-                .short("p")
-                .long("part")
-                .value_name("PART")
-                .help("Part to solve")
+            Arg::with_name("output")
+                .short("o")
+                .long("output")
+                .value_name("FORMAT")
+                .help("How to print answers, for solve and all")
                 .takes_value(true)
-                .default_value("1"),
+                .possible_values(&["text", "json"])
+                .global(true),
         )
-        .arg(
-            clap::Arg::with_name("file")
-                .short("f")
-                .long("file")
-                .value_name("FILE")
-                .help("Data file")
-                .takes_value(true),
+        .subcommand(
+            SubCommand::with_name("solve")
+                .about("Solve one day")
+                .arg(day_arg().required(true))
+                .arg(part_arg().default_value("1"))
+                .arg(file_arg())
+                .arg(input_arg())
+                .arg(
+                    Arg::with_name("animate")
+                        .long("animate")
+                        .help("Draw the simulation as it runs (days 11 and 13)"),
+                )
+                .arg(
+                    Arg::with_name("fps")
+                        .long("fps")
+                        .value_name("N")
+                        .help("Frames per second to animate at, or 0 for as fast as possible")
+                        .takes_value(true)
+                        .default_value("30")
+                        .validator(is_number),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("all")
+                .about("Solve every part of every day and show how long each took"),
+        )
+        .subcommand(
+            SubCommand::with_name("list").about("List the days that can be solved and their inputs"),
+        )
+        .subcommand(
+            SubCommand::with_name("intcode")
+                .about("Run and inspect Intcode programs")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(program_args(
+                    SubCommand::with_name("run").about("Run a program and print its output"),
+                ))
+                .subcommand(
+                    program_args(
+                        SubCommand::with_name("memory")
+                            .about("Run a program and report how its memory changed"),
+                    )
+                    .arg(
+                        Arg::with_name("width")
+                            .short("w")
+                            .long("width")
+                            .value_name("N")
+                            .help("Addresses per heatmap row")
+                            .takes_value(true)
+                            .default_value("64")
+                            .validator(is_positive),
+                    ),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("sif")
+                .about("Decode and draw a day 8 Space Image Format image")
+                .arg(file_arg())
+                .arg(input_arg())
+                .arg(
+                    Arg::with_name("width")
+                        .long("width")
+                        .value_name("N")
+                        .help("Image width")
+                        .takes_value(true)
+                        .default_value("25")
+                        .validator(is_number),
+                )
+                .arg(
+                    Arg::with_name("height")
+                        .long("height")
+                        .value_name("N")
+                        .help("Image height")
+                        .takes_value(true)
+                        .default_value("6")
+                        .validator(is_number),
+                )
+                .arg(
+                    Arg::with_name("stats")
+                        .long("stats")
+                        .help("Print how many pixels of each digit each layer has"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("laser")
+                .about("Vaporise day 10's asteroids, printing the order as CSV by default")
+                .arg(file_arg())
+                .arg(input_arg())
+                .arg(
+                    Arg::with_name("station")
+                        .long("station")
+                        .value_name("X,Y")
+                        .help("Where the laser is [default: the best station]")
+                        .takes_value(true)
+                        .validator(is_pos),
+                )
+                .arg(
+                    Arg::with_name("nth")
+                        .short("n")
+                        .long("nth")
+                        .value_name("N")
+                        .help("Print the Nth asteroid destroyed, counting from 1")
+                        .takes_value(true)
+                        .validator(is_positive),
+                )
+                .arg(
+                    Arg::with_name("target")
+                        .long("target")
+                        .value_name("X,Y")
+                        .help("Print when the asteroid at X,Y is destroyed")
+                        .takes_value(true)
+                        .validator(is_pos)
+                        .conflicts_with("nth"),
+                )
+                .arg(
+                    Arg::with_name("counts")
+                        .long("counts")
+                        .help("Print how many asteroids each asteroid can see, as a heatmap")
+                        .conflicts_with_all(&["station", "nth", "target"]),
+                )
+                .arg(
+                    Arg::with_name("counter-clockwise")
+                        .long("counter-clockwise")
+                        .help("Turn the laser counter-clockwise"),
+                )
+                .arg(
+                    Arg::with_name("start")
+                        .long("start")
+                        .value_name("DX,DY")
+                        .help("Direction to fire in first, with y increasing down")
+                        .takes_value(true)
+                        .default_value("0,-1")
+                        .validator(is_pos),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("bench")
+                .about("Time repeated runs of every day and Intcode program, or of one day")
+                .arg(day_arg())
+                .arg(part_arg().requires("day"))
+                .arg(file_arg().requires("day"))
+                .arg(input_arg().requires("day"))
+                .arg(
+                    Arg::with_name("runs")
+                        .short("n")
+                        .long("runs")
+                        .value_name("N")
+                        .help("Number of runs")
+                        .takes_value(true)
+                        .default_value("10")
+                        .validator(is_number),
+                )
+                .arg(
+                    Arg::with_name("save-baseline")
+                        .long("save-baseline")
+                        .value_name("FILE")
+                        .help("Save the timings to compare later runs against")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("baseline")
+                        .short("b")
+                        .long("baseline")
+                        .value_name("FILE")
+                        .help("Compare the timings against a saved baseline")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("new-day")
+                .about("Add a skeleton solution for a new day")
+                .arg(day_arg().required(true))
+                .arg(
+                    Arg::with_name("crate-dir")
+                        .long("crate-dir")
+                        .value_name("DIR")
+                        .help("Crate to add the day to")
+                        .takes_value(true)
+                        .default_value(scaffold::CRATE_DIR),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("check")
+                .about("Check answers against the expected answers file")
+                .arg(
+                    Arg::with_name("answers")
+                        .short("a")
+                        .long("answers")
+                        .value_name("FILE")
+                        .help("Expected answers")
+                        .takes_value(true)
+                        .default_value("answers.txt"),
+                )
+                .arg(
+                    Arg::with_name("record")
+                        .long("record")
+                        .help("Write the current answers to the answers file"),
+                ),
         )
         .get_matches();
 
+    let data_dir = matches
+        .subcommand()
+        .1
+        .and_then(|m| m.value_of("data-dir"))
+        .or_else(|| matches.value_of("data-dir"));
+    let dirs = input::DataDirs::new(data_dir);
+    let output = matches
+        .subcommand()
+        .1
+        .and_then(|m| m.value_of("output"))
+        .or_else(|| matches.value_of("output"))
+        .map_or(report::Output::Text, |o| o.parse().unwrap());
+    // Only the answers of `solve` and `all` can be printed as JSON.
+    let command = matches.subcommand_name().unwrap_or("");
+    let result = match matches.subcommand() {
+        _ if output == report::Output::Json && !["solve", "all"].contains(&command) => {
+            Err(format!("{} can't print --output json", command))
+        }
+        ("solve", Some(m)) => solve_cmd(m, &dirs, output),
+        ("all", Some(_)) => all_cmd(&dirs, output),
+        ("list", Some(_)) => list_cmd(&dirs),
+        ("intcode", Some(m)) => match m.subcommand() {
+            ("run", Some(m)) => intcode_run_cmd(m),
+            ("memory", Some(m)) => intcode_memory_cmd(m),
+            _ => unreachable!(),
+        },
+        ("sif", Some(m)) => sif_cmd(m, &dirs),
+        ("laser", Some(m)) => laser_cmd(m, &dirs),
+        ("bench", Some(m)) => bench_cmd(m, &dirs),
+        ("check", Some(m)) => check_cmd(m, &dirs),
+        ("new-day", Some(m)) => new_day_cmd(m, &dirs),
+        _ => unreachable!(),
+    };
+
+    if let Err(e) = result {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+/// Read the input chosen by the `file` and `input` arguments.
+fn read_input(dirs: &input::DataDirs, day: u8, matches: &ArgMatches) -> Result<String, String> {
+    let data = match matches.value_of("file") {
+        Some(file) => input::read_file(file),
+        None => dirs.read(&input::name(day, matches.value_of("input"))),
+    };
+    data.map_err(|e| e.to_string())
+}
+
+fn day_and_part(matches: &ArgMatches) -> Result<(u8, u8), String> {
     let day = matches.value_of("day").unwrap();
     let part = matches.value_of("part").unwrap();
-    let file = matches.value_of("file");
-
-    match (day.parse::<u8>(), part.parse::<u8>()) {
-        (Ok(day), Ok(part)) => {
-            let def_file = format!("../ocaml/data/day_{:02}.txt", day);
-            let file = file.unwrap_or(&def_file);
-            let data = std::fs::read_to_string(file);
-            match day {
-                1 => day_01::solve(part, data),
-                2 => day_02::solve(part, data),
-                3 => day_03::solve(part, data),
-                4 => day_04::solve(part, data),
-                5 => day_05::solve(part, data),
-                6 => day_06::solve(part, data),
-                7 => day_07::solve(part, data),
-                8 => day_08::solve(part, data),
-                9 => day_09::solve(part, data),
-                10 => day_10::solve(part, data),
-                11 => day_11::solve(part, data),
-                12 => day_12::solve(part, data),
-                13 => day_13::solve(part, data),
-                _ => (),
+    let day = day
+        .parse()
+        .map_err(|_| format!("day {} is not implemented", day))?;
+    // Validated by clap.
+    let part = part.parse().unwrap();
+    Ok((day, part))
+}
+
+fn solve_cmd(
+    matches: &ArgMatches,
+    dirs: &input::DataDirs,
+    output: report::Output,
+) -> Result<(), String> {
+    let (day, part) = day_and_part(matches)?;
+    if matches.is_present("animate") {
+        if output == report::Output::Json {
+            return Err("--animate can't be used with --output json".to_string());
+        }
+        return animate_cmd(matches, dirs, day, part);
+    }
+    // Report a day that isn't implemented before looking for its input.
+    let result = solution::find(day)
+        .map_err(|e| e.to_string())
+        .and_then(|solution| {
+            let data = read_input(dirs, day, matches)?;
+            solution.run(part, &data).map_err(|e| e.to_string())
+        });
+    let row = report::Row { day, part, result };
+    match (output, &row.result) {
+        (report::Output::Json, _) => println!("{}", row.to_json()),
+        (report::Output::Text, Ok(run)) => println!("{}", run.answer),
+        (report::Output::Text, Err(_)) => (),
+    }
+    row.result.map(|_| ())
+}
+
+fn animate_cmd(
+    matches: &ArgMatches,
+    dirs: &input::DataDirs,
+    day: u8,
+    part: u8,
+) -> Result<(), String> {
+    let fps = matches
+        .value_of("fps")
+        .unwrap()
+        .parse()
+        .map_err(|_| "fps must be between 0 and 2^32".to_string())?;
+    let animate: fn(&[i64], u8, &mut animate::Animation<_>) -> Result<_, _> = match day {
+        11 => day_11::animate,
+        13 => day_13::animate,
+        _ => return Err(format!("day {} can't be animated", day)),
+    };
+    let data = read_input(dirs, day, matches)?;
+    let memory = intcode::parse(&data).map_err(|e| format!("couldn't parse input: {}", e))?;
+    let mut animation = animate::Animation::stdout(fps);
+    let answer = animate(&memory, part, &mut animation)?;
+    drop(animation);
+    println!("{}", answer);
+    Ok(())
+}
+
+fn all_cmd(dirs: &input::DataDirs, output: report::Output) -> Result<(), String> {
+    let table = runner::run_all(dirs);
+    match output {
+        report::Output::Json => {
+            for row in table.0.iter() {
+                println!("{}", row.to_json());
+            }
+        }
+        report::Output::Text => print!("{}", table),
+    }
+    match table.failures() {
+        0 => Ok(()),
+        failed => Err(format!("{} parts failed", failed)),
+    }
+}
+
+fn list_cmd(dirs: &input::DataDirs) -> Result<(), String> {
+    for solution in DAYS {
+        let day = solution.day();
+        println!("{:2}: {}", day, dirs.discover(day).join(" "));
+    }
+    Ok(())
+}
+
+fn sif_cmd(matches: &ArgMatches, dirs: &input::DataDirs) -> Result<(), String> {
+    let dimension = |name| {
+        let n = matches.value_of(name).unwrap();
+        n.parse().map_err(|_| format!("{} {} is too large", name, n))
+    };
+    let size = day_08::Size::new(dimension("width")?, dimension("height")?);
+    let data = read_input(dirs, 8, matches)?;
+    let image = day_08::Image::from_str(&data, size)
+        .map_err(|e| format!("couldn't parse input: {}", e))?;
+
+    if matches.is_present("stats") {
+        let stats = image.layer_stats();
+        let digits: Vec<u8> = (0..10)
+            .filter(|&d| stats.iter().any(|layer| layer.count(d).unwrap_or(0) > 0))
+            .collect();
+        print!("layer");
+        for d in digits.iter() {
+            print!(" {:>5}", d);
+        }
+        println!();
+        for (i, layer) in stats.iter().enumerate() {
+            print!("{:>5}", i + 1);
+            for &d in digits.iter() {
+                print!(" {:>5}", layer.count(d).unwrap_or(0));
             }
+            println!();
         }
-        _ => (),
-    }
-}
-
-mod grid;
-mod intcode;
-
-mod day_01;
-mod day_02;
-mod day_03;
-mod day_04;
-mod day_05;
-mod day_06;
-mod day_07;
-mod day_08;
-mod day_09;
-mod day_10;
-mod day_11;
-mod day_12;
-mod day_13;
+        return Ok(());
+    }
+    print!("{}", image);
+    image.render().map(|_| ()).map_err(|e| e.to_string())
+}
+
+fn laser_cmd(matches: &ArgMatches, dirs: &input::DataDirs) -> Result<(), String> {
+    let data = read_input(dirs, 10, matches)?;
+    let grid: Grid<day_10::Roid> = data
+        .parse()
+        .map_err(|e| format!("couldn't parse input: {}", e))?;
+    if matches.is_present("counts") {
+        print!("{}", day_10::render_counts(&grid.to_counts()));
+        return Ok(());
+    }
+    // Validated by clap.
+    let pos = |name| matches.value_of(name).map(|p| p.parse::<Pos>().unwrap());
+    let station = match pos("station") {
+        Some(station) => station,
+        None => *grid.solve().ok_or("there are no asteroids")?,
+    };
+    let start = pos("start").unwrap();
+    let start = Ray::new(start.x, start.y).ok_or("the starting direction can't be 0,0")?;
+    let sweep = day_10::Sweep {
+        rotation: match matches.is_present("counter-clockwise") {
+            true => day_10::Rotation::CounterClockwise,
+            false => day_10::Rotation::Clockwise,
+        },
+        start,
+    };
+    let order = grid.vaporise(&station, &sweep);
+
+    if let Some(n) = matches.value_of("nth") {
+        let n = n.parse().map_err(|_| format!("{} is too large", n))?;
+        let shot = order
+            .nth(n)
+            .ok_or_else(|| format!("only {} asteroids are destroyed", order.0.len()))?;
+        println!("{}", shot.pos);
+    } else if let Some(target) = pos("target") {
+        let (n, shot) = order
+            .find(&target)
+            .ok_or_else(|| format!("no asteroid at {} is destroyed", target))?;
+        println!(
+            "{} is destroyed by shot {}, in rotation {}",
+            target, n, shot.rotation
+        );
+    } else {
+        print!("{}", order.to_csv());
+    }
+    Ok(())
+}
+
+fn bench_cmd(matches: &ArgMatches, dirs: &input::DataDirs) -> Result<(), String> {
+    let runs: u32 = match matches.value_of("runs").unwrap().parse() {
+        Ok(runs) if runs > 0 => runs,
+        _ => return Err("runs must be between 1 and 2^32".to_string()),
+    };
+    let baseline = match matches.value_of("baseline") {
+        Some(file) => Some(
+            std::fs::read_to_string(file)
+                .map_err(|e| format!("couldn't read {}: {}", file, e))?
+                .parse::<bench::Results>()
+                .map_err(|e| format!("couldn't parse {}: {}", file, e))?,
+        ),
+        None => None,
+    };
+
+    let results = match matches.value_of("day") {
+        Some(day) => {
+            let day = day
+                .parse()
+                .map_err(|_| format!("day {} is not implemented", day))?;
+            let solution = solution::find(day).map_err(|e| e.to_string())?;
+            let data = read_input(dirs, day, matches)?;
+            let name = match matches.value_of("file") {
+                Some(file) => format!("{}[{}]", input::name(day, None), file),
+                None => input::name(day, matches.value_of("input")),
+            };
+            let parts = match matches.value_of("part") {
+                // Validated by clap.
+                Some(part) => vec![part.parse().unwrap()],
+                None => PARTS.to_vec(),
+            };
+            bench::day(solution, &name, &parts, &data, runs)?
+        }
+        None => bench::all(dirs, DAYS, runs)?,
+    };
+    print!("{}", results.compare(baseline.as_ref()));
+
+    if let Some(file) = matches.value_of("save-baseline") {
+        std::fs::write(file, results.to_string())
+            .map_err(|e| format!("couldn't write {}: {}", file, e))?;
+    }
+    Ok(())
+}
+
+fn check_cmd(matches: &ArgMatches, dirs: &input::DataDirs) -> Result<(), String> {
+    let file = matches.value_of("answers").unwrap();
+    let record = matches.is_present("record");
+    let mut answers = match std::fs::read_to_string(file) {
+        Ok(s) => s
+            .parse::<answers::Answers>()
+            .map_err(|e| format!("couldn't parse {}: {}", file, e))?,
+        Err(_) if record => answers::Answers::default(),
+        Err(e) => return Err(format!("couldn't read {}: {}", file, e)),
+    };
+
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    for check in runner::check(dirs, &answers) {
+        let outcome = match (record, check.actual) {
+            (true, Ok(actual)) => {
+                answers.insert(&check.input, check.part, actual);
+                answers::Outcome::Pass
+            }
+            _ => check.outcome,
+        };
+        println!("{} part {}: {}", check.input, check.part, outcome);
+        match outcome {
+            answers::Outcome::Pass => passed += 1,
+            answers::Outcome::Missing { .. } | answers::Outcome::Unsolved => missing += 1,
+            _ => failed += 1,
+        }
+    }
+    println!("{} passed, {} failed, {} missing", passed, failed, missing);
+
+    if record {
+        std::fs::write(file, answers.to_string())
+            .map_err(|e| format!("couldn't write {}: {}", file, e))?;
+    }
+    match failed {
+        0 => Ok(()),
+        failed => Err(format!("{} parts failed", failed)),
+    }
+}
+
+fn new_day_cmd(matches: &ArgMatches, dirs: &input::DataDirs) -> Result<(), String> {
+    let day = matches.value_of("day").unwrap();
+    let day: u8 = match day.parse() {
+        Ok(day) if (1..=25).contains(&day) => day,
+        _ => return Err(format!("there is no day {}", day)),
+    };
+    let crate_dir = std::path::Path::new(matches.value_of("crate-dir").unwrap());
+    let data_dir = dirs
+        .0
+        .iter()
+        .find(|dir| dir.is_dir())
+        .ok_or_else(|| format!("none of the data directories exist: {:?}", dirs.0))?;
+    for path in scaffold::new_day(crate_dir, data_dir, day)? {
+        println!("wrote {}", path.display());
+    }
+    Ok(())
+}
+
+fn load_program(matches: &ArgMatches) -> Result<intcode::Program, String> {
+    let file = matches.value_of("program").unwrap();
+    let data =
+        std::fs::read_to_string(file).map_err(|e| format!("couldn't read {}: {}", file, e))?;
+    let memory =
+        intcode::parse(&data).map_err(|e| format!("couldn't parse {}: {}", file, e))?;
+    let mut program = intcode::Program::new(memory);
+
+    for set in matches.values_of("set").into_iter().flatten() {
+        let bad_set = || format!("expected ADDR=VAL, got '{}'", set);
+        let eq = set.find('=').ok_or_else(bad_set)?;
+        let addr: usize = set[..eq].parse().map_err(|_| bad_set())?;
+        let val: i64 = set[eq + 1..].parse().map_err(|_| bad_set())?;
+        if addr >= program.memory.len() {
+            program.memory.resize(addr + 1, 0);
+        }
+        program.memory[addr] = val;
+    }
+    Ok(program)
+}
+
+fn program_input(matches: &ArgMatches) -> Result<Vec<i64>, String> {
+    let input = matches.value_of("input").unwrap();
+    input
+        .split(',')
+        .map(|s| s.parse::<i64>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("bad input '{}'", input))
+}
+
+fn program_steps(matches: &ArgMatches) -> usize {
+    matches
+        .value_of("steps")
+        .map_or(usize::MAX, |s| s.parse().unwrap_or(usize::MAX))
+}
+
+fn intcode_run_cmd(matches: &ArgMatches) -> Result<(), String> {
+    let mut program = load_program(matches)?;
+    let input = program_input(matches)?;
+    let output = program.run_for(&mut input.into_iter().cycle(), program_steps(matches));
+    for o in output {
+        println!("{}", o);
+    }
+    Ok(())
+}
+
+fn intcode_memory_cmd(matches: &ArgMatches) -> Result<(), String> {
+    let mut program = load_program(matches)?;
+    let input = program_input(matches)?;
+    let width = matches.value_of("width").unwrap().parse().unwrap();
+
+    let before = program.memory.clone();
+    program.trace_access();
+    let output = program.run_for(&mut input.into_iter().cycle(), program_steps(matches));
+    let access = program.access().unwrap();
+
+    println!("state: {:?}, {} outputs", program.state, output.len());
+    println!("changed:");
+    print!("{}", memory::diff(&before, &program.memory));
+    println!("reads:");
+    print!("{}", memory::Heatmap::reads(access, width));
+    println!("writes:");
+    let writes = memory::Heatmap::writes(access, width);
+    print!("{}", writes);
+    println!("most written:");
+    for (addr, count) in writes.hottest(10) {
+        println!("{:>6} {:>8} (now {})", addr, count, program.memory[addr]);
+    }
+    Ok(())
+}
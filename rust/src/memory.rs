@@ -0,0 +1,168 @@
+//! Inspecting Intcode memory: the ranges of addresses that differ between two
+//! snapshots, and a heatmap of how often each address was accessed.
+
+use crate::intcode::Access;
+
+/// A run of consecutive addresses whose values differ between two snapshots.
+#[derive(Debug, PartialEq)]
+pub struct ChangedRange {
+    pub start: usize,
+    pub before: Vec<i64>,
+    pub after: Vec<i64>,
+}
+
+impl ChangedRange {
+    pub fn end(&self) -> usize {
+        self.start + self.after.len()
+    }
+}
+
+impl std::fmt::Display for ChangedRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{:>6}..{:<6} {:?} -> {:?}",
+            self.start,
+            self.end(),
+            self.before,
+            self.after
+        )
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct MemoryDiff(pub Vec<ChangedRange>);
+
+/// Compare two memory snapshots. Memory beyond the end of a snapshot is
+/// treated as zero, as it is by `Program`.
+pub fn diff(before: &[i64], after: &[i64]) -> MemoryDiff {
+    let len = std::cmp::max(before.len(), after.len());
+    let get = |mem: &[i64], addr: usize| mem.get(addr).copied().unwrap_or(0);
+
+    let mut ranges: Vec<ChangedRange> = Vec::new();
+    for addr in 0..len {
+        let (b, a) = (get(before, addr), get(after, addr));
+        if a == b {
+            continue;
+        }
+        match ranges.last_mut() {
+            Some(range) if range.end() == addr => {
+                range.before.push(b);
+                range.after.push(a);
+            }
+            _ => ranges.push(ChangedRange {
+                start: addr,
+                before: vec![b],
+                after: vec![a],
+            }),
+        }
+    }
+    MemoryDiff(ranges)
+}
+
+impl std::fmt::Display for MemoryDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for range in self.0.iter() {
+            writeln!(f, "{}", range)?;
+        }
+        Ok(())
+    }
+}
+
+const SHADES: &[u8] = b" .:-=+*#%@";
+
+/// Per-address access counts, displayed as rows of `width` addresses with
+/// denser glyphs for more frequently accessed addresses.
+pub struct Heatmap {
+    pub counts: Vec<usize>,
+    pub width: usize,
+}
+
+impl Heatmap {
+    pub fn reads(access: &[Access], width: usize) -> Self {
+        Self {
+            counts: access.iter().map(|a| a.reads).collect(),
+            width,
+        }
+    }
+
+    pub fn writes(access: &[Access], width: usize) -> Self {
+        Self {
+            counts: access.iter().map(|a| a.writes).collect(),
+            width,
+        }
+    }
+
+    fn shade(&self, count: usize, max: usize) -> char {
+        if count == 0 {
+            return SHADES[0] as char;
+        }
+        let i = std::cmp::max(1, count * (SHADES.len() - 1) / max);
+        SHADES[i] as char
+    }
+
+    /// The `n` most accessed addresses, most accessed first.
+    pub fn hottest(&self, n: usize) -> Vec<(usize, usize)> {
+        let mut counts: Vec<(usize, usize)> = self
+            .counts
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|&(_, c)| c > 0)
+            .collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        counts.truncate(n);
+        counts
+    }
+}
+
+impl std::fmt::Display for Heatmap {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let max = self.counts.iter().copied().max().unwrap_or(0);
+        for (row, counts) in self.counts.chunks(self.width).enumerate() {
+            write!(f, "{:>6} ", row * self.width)?;
+            for &count in counts {
+                write!(f, "{}", self.shade(count, max))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        let before = [1, 2, 3, 4, 5, 6];
+        let after = [1, 0, 0, 4, 5, 7, 8];
+        assert_eq!(
+            diff(&before, &after),
+            MemoryDiff(vec![
+                ChangedRange {
+                    start: 1,
+                    before: vec![2, 3],
+                    after: vec![0, 0],
+                },
+                ChangedRange {
+                    start: 5,
+                    before: vec![6, 0],
+                    after: vec![7, 8],
+                },
+            ])
+        );
+        assert_eq!(diff(&before, &before), MemoryDiff(vec![]));
+    }
+
+    #[test]
+    fn test_heatmap() {
+        let heatmap = Heatmap {
+            counts: vec![0, 1, 9, 0, 5, 0],
+            width: 4,
+        };
+        assert_eq!(heatmap.to_string(), "     0  .@ \n     4 + \n");
+        assert_eq!(heatmap.hottest(2), vec![(2, 9), (4, 5)]);
+    }
+}
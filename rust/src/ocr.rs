@@ -0,0 +1,210 @@
+//! Reading letters drawn in the 4x6 font used by puzzles that render their
+//! answer as an image, such as day 8 and day 11.
+
+use crate::grid::{Grid, Pos};
+
+const HEIGHT: usize = 6;
+/// Letters are drawn every five columns. Most are four wide with a blank
+/// column after them, but some, like `Y`, fill all five.
+const PITCH: usize = 5;
+
+/// Each letter without the blank columns around it.
+const GLYPHS: &[(char, [&str; HEIGHT])] = &[
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('D', ["###.", "#..#", "#..#", "#..#", "#..#", "###."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', ["###", ".#.", ".#.", ".#.", ".#.", "###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// The lit pixels aren't six rows high.
+    BadHeight(usize),
+    /// The letter at this index isn't in the font; the glyph is included
+    /// with `#` for lit pixels.
+    UnknownGlyph(usize, String),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::BadHeight(h) => write!(f, "expected letters {} pixels high, got {}", HEIGHT, h),
+            Error::UnknownGlyph(i, glyph) => write!(f, "unknown letter {}:\n{}", i, glyph),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Read the letters in an image given as rows of pixels, `true` where lit.
+/// Blank rows and columns around the letters are ignored.
+pub fn recognize(rows: &[Vec<bool>]) -> Result<String, Error> {
+    let row_lit = |r: &Vec<bool>| r.iter().any(|&p| p);
+    let rows = match (
+        rows.iter().position(row_lit),
+        rows.iter().rposition(row_lit),
+    ) {
+        (Some(top), Some(bottom)) => &rows[top..=bottom],
+        _ => &rows[0..0],
+    };
+    if rows.len() != HEIGHT {
+        return Err(Error::BadHeight(rows.len()));
+    }
+
+    let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+    let lit = |x: usize, y: usize| rows[y].get(x).copied().unwrap_or(false);
+    let column_lit = |x: usize| (0..HEIGHT).any(|y| lit(x, y));
+    let left = (0..width).find(|&x| column_lit(x)).unwrap_or(width);
+    let right = (0..width)
+        .rev()
+        .find(|&x| column_lit(x))
+        .map_or(0, |x| x + 1);
+
+    let letters = read_letters(left, right, lit);
+    if letters.is_ok() {
+        return letters;
+    }
+    // A letter with blank columns on its left, like `I`, may start the
+    // image, so its cell may start a little further left.
+    (left.saturating_sub(PITCH - 1)..left)
+        .rev()
+        .map(|start| read_letters(start, right, lit))
+        .find(|letters| letters.is_ok())
+        .unwrap_or(letters)
+}
+
+/// Read the letters in the columns from `left` to `right`, one every `PITCH`
+/// columns.
+fn read_letters(
+    left: usize,
+    right: usize,
+    lit: impl Fn(usize, usize) -> bool,
+) -> Result<String, Error> {
+    let mut letters = String::new();
+    for (i, x0) in (left..right).step_by(PITCH).enumerate() {
+        let columns: Vec<usize> = (x0..x0 + PITCH)
+            .filter(|&x| (0..HEIGHT).any(|y| lit(x, y)))
+            .collect();
+        let glyph: Vec<String> = (0..HEIGHT)
+            .map(|y| match (columns.first(), columns.last()) {
+                (Some(&first), Some(&last)) => (first..=last)
+                    .map(|x| if lit(x, y) { '#' } else { '.' })
+                    .collect(),
+                _ => String::new(),
+            })
+            .collect();
+        let letter = GLYPHS
+            .iter()
+            .find(|(_, g)| g.iter().zip(glyph.iter()).all(|(a, b)| a == b))
+            .map(|(c, _)| *c)
+            .ok_or_else(|| Error::UnknownGlyph(i, glyph.join("\n")))?;
+        letters.push(letter);
+    }
+    Ok(letters)
+}
+
+/// The pixels of a grid within its bounding box, lit where `is_lit` holds.
+pub fn grid_pixels<T>(grid: &Grid<T>, is_lit: impl Fn(&T) -> bool) -> Vec<Vec<bool>> {
+    let lit: Vec<&Pos> = grid
+        .0
+        .iter()
+        .filter(|(_, v)| is_lit(v))
+        .map(|(p, _)| p)
+        .collect();
+    let (min_x, max_x) = match (lit.iter().map(|p| p.x).min(), lit.iter().map(|p| p.x).max()) {
+        (Some(min), Some(max)) => (min, max),
+        _ => return vec![],
+    };
+    let min_y = lit.iter().map(|p| p.y).min().unwrap();
+    let max_y = lit.iter().map(|p| p.y).max().unwrap();
+    (min_y..=max_y)
+        .map(|y| {
+            (min_x..=max_x)
+                .map(|x| grid.0.get(&Pos::new(x, y)).is_some_and(&is_lit))
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pixels(s: &str) -> Vec<Vec<bool>> {
+        s.lines()
+            .map(|l| l.chars().map(|c| c == '#').collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_recognize() {
+        let image = "\
+..........
+.#..#.###.
+.#..#.#..#
+.####.###.
+.#..#.#..#
+.#..#.#..#
+.#..#.###.
+";
+        assert_eq!(recognize(&pixels(image)), Ok("HB".to_string()));
+    }
+
+    #[test]
+    fn test_missing_letters() {
+        // I and Y, first in the image and after another letter, as they're
+        // drawn in five-column cells.
+        let image = "\
+.###.#...#.###.
+..#..#...#.#..#
+..#...#.#..#..#
+..#....#...#..#
+..#....#...#..#
+.###...#...###.
+";
+        assert_eq!(recognize(&pixels(image)), Ok("IYD".to_string()));
+        let image = "\
+###...###.#...#
+#..#...#..#...#
+#..#...#...#.#.
+#..#...#....#..
+#..#...#....#..
+###...###...#..
+";
+        assert_eq!(recognize(&pixels(image)), Ok("DIY".to_string()));
+    }
+
+    #[test]
+    fn test_unknown_glyph() {
+        let image = "####\n####\n####\n####\n####\n####\n";
+        assert_eq!(
+            recognize(&pixels(image)),
+            Err(Error::UnknownGlyph(0, image.trim_end().to_string()))
+        );
+        assert_eq!(recognize(&pixels("#\n#\n")), Err(Error::BadHeight(2)));
+    }
+
+    #[test]
+    fn test_grid_pixels() {
+        let grid: Grid<char> = "..#\n.#.\n".parse().unwrap();
+        assert_eq!(
+            grid_pixels(&grid, |&c| c == '#'),
+            vec![vec![false, true], vec![true, false]]
+        );
+    }
+}
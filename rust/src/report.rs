@@ -0,0 +1,232 @@
+use std::time::Duration;
+
+use crate::solution::{Error, Run};
+
+/// How answers are printed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Output {
+    Text,
+    /// One JSON object per line.
+    Json,
+}
+
+impl std::str::FromStr for Output {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Output::Text),
+            "json" => Ok(Output::Json),
+            _ => Err(format!("unknown output format: {}", s)),
+        }
+    }
+}
+
+/// The outcome of running one part of one day.
+pub struct Row {
+    pub day: u8,
+    pub part: u8,
+    pub result: Result<Run, String>,
+}
+
+impl Row {
+    fn answer(&self) -> String {
+        match &self.result {
+            Ok(run) if run.answer.trim_end().contains('\n') => {
+                format!("({} lines)", run.answer.trim_end().lines().count())
+            }
+            Ok(run) => run.answer.clone(),
+            Err(e) => format!("error: {}", e),
+        }
+    }
+
+    /// The row as a single-line JSON object. Multi-line answers are kept as
+    /// text, with their lines separated by `\n`.
+    pub fn to_json(&self) -> String {
+        match &self.result {
+            Ok(run) => format!(
+                r#"{{"day":{},"part":{},"answer":{},"elapsed_ms":{:.3}}}"#,
+                self.day,
+                self.part,
+                json_string(run.answer.trim_end_matches('\n')),
+                run.total_time().as_secs_f64() * 1000.
+            ),
+            Err(e) => format!(
+                r#"{{"day":{},"part":{},"answer":null,"error":{}}}"#,
+                self.day,
+                self.part,
+                json_string(e)
+            ),
+        }
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+pub fn format_duration(d: Duration) -> String {
+    format!("{:.3}ms", d.as_secs_f64() * 1000.)
+}
+
+/// A table of answers and timings, followed by any multi-line answers.
+pub struct Table(pub Vec<Row>);
+
+impl Table {
+    pub fn total_time(&self) -> Duration {
+        self.0
+            .iter()
+            .filter_map(|row| row.result.as_ref().ok())
+            .map(|run| run.total_time())
+            .sum()
+    }
+
+    /// The parts that failed to run, not counting those not solved yet.
+    pub fn failures(&self) -> usize {
+        self.0
+            .iter()
+            .filter(|row| match &row.result {
+                Ok(_) => false,
+                Err(e) => !Error::is_unsolved(e),
+            })
+            .count()
+    }
+}
+
+impl std::fmt::Display for Table {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let answers: Vec<String> = self.0.iter().map(|row| row.answer()).collect();
+        let width = answers.iter().map(|a| a.len()).max().unwrap_or(0);
+        let width = std::cmp::max(width, "answer".len());
+        let line = |f: &mut std::fmt::Formatter, cols: [&str; 5]| {
+            let line = format!(
+                "{:>3} {:>4}  {:<width$}  {:>12} {:>12}",
+                cols[0],
+                cols[1],
+                cols[2],
+                cols[3],
+                cols[4],
+                width = width
+            );
+            writeln!(f, "{}", line.trim_end())
+        };
+
+        line(f, ["day", "part", "answer", "parse", "solve"])?;
+        for (row, answer) in self.0.iter().zip(answers.iter()) {
+            let (parse, solve) = match &row.result {
+                Ok(run) => (
+                    format_duration(run.parse_time),
+                    format_duration(run.solve_time),
+                ),
+                Err(_) => (String::new(), String::new()),
+            };
+            let (day, part) = (row.day.to_string(), row.part.to_string());
+            line(f, [&day, &part, answer, &parse, &solve])?;
+        }
+        let total = format_duration(self.total_time());
+        line(f, ["", "", "total", "", &total])?;
+
+        for row in self.0.iter() {
+            if let Ok(run) = &row.result {
+                if run.answer.trim_end().contains('\n') {
+                    let answer = run.answer.trim_end();
+                    writeln!(f, "\nDay {} part {}:\n{}", row.day, row.part, answer)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(answer: &str, ms: u64) -> Result<Run, String> {
+        Ok(Run {
+            answer: answer.to_string(),
+            parse_time: Duration::from_millis(ms),
+            solve_time: Duration::from_millis(ms),
+        })
+    }
+
+    #[test]
+    fn test_json() {
+        let row = Row {
+            day: 8,
+            part: 2,
+            result: run("█ \"\\\n #\n", 1),
+        };
+        assert_eq!(
+            row.to_json(),
+            r#"{"day":8,"part":2,"answer":"█ \"\\\n #","elapsed_ms":2.000}"#
+        );
+        let row = Row {
+            day: 14,
+            part: 1,
+            result: Err("day 14 is not implemented".to_string()),
+        };
+        assert_eq!(
+            row.to_json(),
+            r#"{"day":14,"part":1,"answer":null,"error":"day 14 is not implemented"}"#
+        );
+    }
+
+    #[test]
+    fn test_table() {
+        let table = Table(vec![
+            Row {
+                day: 1,
+                part: 1,
+                result: run("42", 1),
+            },
+            Row {
+                day: 1,
+                part: 2,
+                result: Err("no answer".to_string()),
+            },
+            Row {
+                day: 8,
+                part: 2,
+                result: run("#.\n.#\n", 2),
+            },
+        ]);
+
+        assert_eq!(table.total_time(), Duration::from_millis(6));
+        assert_eq!(table.failures(), 1);
+        let unsolved = Table(vec![Row {
+            day: 14,
+            part: 1,
+            result: Err(Error::Unsolved.to_string()),
+        }]);
+        assert_eq!(unsolved.failures(), 0);
+        assert_eq!(
+            table.to_string(),
+            "\
+day part  answer                   parse        solve
+  1    1  42                     1.000ms      1.000ms
+  1    2  error: no answer
+  8    2  (2 lines)              2.000ms      2.000ms
+          total                               6.000ms
+
+Day 8 part 2:
+#.
+.#
+"
+        );
+    }
+}
@@ -0,0 +1,68 @@
+//! Running solutions over their inputs.
+
+use crate::answers::{Answers, Outcome};
+use crate::input::{self, DataDirs};
+use crate::report::{Row, Table};
+use crate::solution::{self, Answer, Run, DAYS, PARTS};
+
+/// Run one part of a day on the given input data.
+pub fn run(day: u8, part: u8, data: &str) -> Result<Run, String> {
+    let solution = solution::find(day).map_err(|e| e.to_string())?;
+    solution.run(part, data).map_err(|e| e.to_string())
+}
+
+/// Run one part of a day on a named input from the data directory.
+pub fn run_input(dirs: &DataDirs, day: u8, part: u8, input: &str) -> Result<Run, String> {
+    let data = dirs.read(input).map_err(|e| e.to_string())?;
+    run(day, part, &data)
+}
+
+/// Run every part of every day on its default input.
+pub fn run_all(dirs: &DataDirs) -> Table {
+    let mut rows = vec![];
+    for solution in DAYS {
+        let day = solution.day();
+        for &part in PARTS {
+            let result = run_input(dirs, day, part, &input::name(day, None));
+            rows.push(Row { day, part, result });
+        }
+    }
+    Table(rows)
+}
+
+/// The answer to one part on one input, compared against the expected one.
+pub struct Check {
+    pub input: String,
+    pub part: u8,
+    pub actual: Result<Answer, String>,
+    pub outcome: Outcome,
+}
+
+/// Check every day's default input, and every input with expected answers.
+pub fn check(dirs: &DataDirs, answers: &Answers) -> Vec<Check> {
+    let mut inputs: Vec<String> = DAYS.iter().map(|s| input::name(s.day(), None)).collect();
+    for input in answers.0.keys() {
+        if !inputs.contains(input) {
+            inputs.push(input.clone());
+        }
+    }
+
+    let mut checks = vec![];
+    for input in inputs {
+        let day = input::day_of(&input);
+        for &part in PARTS {
+            let actual = match day {
+                Some(day) => run_input(dirs, day, part, &input).map(|run| run.answer),
+                None => Err(format!("{} is not named after a day", input)),
+            };
+            let outcome = Outcome::new(answers.get(&input, part), actual.clone());
+            checks.push(Check {
+                input: input.clone(),
+                part,
+                actual,
+                outcome,
+            });
+        }
+    }
+    checks
+}
@@ -0,0 +1,229 @@
+//! Generating the skeleton of a new day: its module, its registration in
+//! `lib.rs` and `DAYS`, and an empty input file.
+
+use std::path::{Path, PathBuf};
+
+/// The crate that new days are added to, unless another is given.
+pub const CRATE_DIR: &str = env!("CARGO_MANIFEST_DIR");
+
+const MAX_WIDTH: usize = 100;
+
+/// The source of a new day's module. Its parts return `Error::Unsolved`, so
+/// that `check`, `all` and the answers tests count them as missing rather
+/// than failed until they're solved and their answers recorded.
+pub fn module(day: u8) -> String {
+    format!(
+        r#"use crate::solution::{{Answer, Error, Solution}};
+
+pub struct Day{day:02};
+
+impl Solution for Day{day:02} {{
+    const DAY: u8 = {day};
+
+    type Input = Vec<String>;
+
+    fn parse(data: &str) -> Result<Self::Input, Error> {{
+        Ok(data.lines().map(String::from).collect())
+    }}
+
+    fn part_1(input: &Self::Input) -> Result<Answer, Error> {{
+        part_1::solve(input)
+    }}
+
+    fn part_2(input: &Self::Input) -> Result<Answer, Error> {{
+        part_2::solve(input)
+    }}
+}}
+{part_1}{part_2}"#,
+        day = day,
+        part_1 = part_module(1),
+        part_2 = part_module(2),
+    )
+}
+
+fn part_module(part: u8) -> String {
+    format!(
+        r#"
+pub mod part_{part} {{
+    use crate::solution::{{Answer, Error}};
+
+    pub fn solve(_input: &[String]) -> Result<Answer, Error> {{
+        Err(Error::Unsolved)
+    }}
+
+    #[cfg(test)]
+    mod tests {{
+        use super::*;
+        #[test]
+        #[ignore]
+        fn it_works() {{
+            let input = vec!["example".to_string()];
+            assert_eq!(solve(&input), Ok("answer".to_string()));
+        }}
+    }}
+}}
+"#,
+        part = part
+    )
+}
+
+fn day_of_line(line: &str, prefix: &str) -> Option<u8> {
+    let rest = line.trim().strip_prefix(prefix)?;
+    rest.get(..2)?.parse().ok()
+}
+
+/// Insert `line` among the lines that start with `prefix` followed by a day,
+/// keeping them in order of day.
+fn insert_line(source: &str, prefix: &str, day: u8, line: &str) -> Result<String, String> {
+    let lines: Vec<&str> = source.lines().collect();
+    let days: Vec<(usize, u8)> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, l)| day_of_line(l, prefix).map(|d| (i, d)))
+        .collect();
+    if days.iter().any(|&(_, d)| d == day) {
+        return Err(format!("day {} is already registered", day));
+    }
+    let at = match days.iter().find(|&&(_, d)| d > day) {
+        Some(&(i, _)) => i,
+        None => match days.last() {
+            Some(&(i, _)) => i + 1,
+            None => return Err(format!("couldn't find any lines starting {}", prefix)),
+        },
+    };
+    let mut lines: Vec<String> = lines.into_iter().map(String::from).collect();
+    lines.insert(at, line.to_string());
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Add `pub mod day_NN;` to `lib.rs`.
+pub fn register_module(lib: &str, day: u8) -> Result<String, String> {
+    insert_line(
+        lib,
+        "pub mod day_",
+        day,
+        &format!("pub mod day_{:02};", day),
+    )
+}
+
+/// Import the day's module in `solution.rs` and add it to `DAYS`.
+pub fn register_solution(solution: &str, day: u8) -> Result<String, String> {
+    let entry = format!("    &day_{:02}::Day{:02},", day, day);
+    let solution = insert_line(solution, "&day_", day, &entry)?;
+
+    let start = solution
+        .find("use crate::{")
+        .ok_or("couldn't find the imports of the days")?;
+    let end = start
+        + solution[start..]
+            .find("};")
+            .ok_or("couldn't find the end of the imports of the days")?;
+    let mut modules: Vec<String> = solution[start + "use crate::{".len()..end]
+        .split(',')
+        .map(|m| m.trim().to_string())
+        .filter(|m| !m.is_empty())
+        .collect();
+    modules.push(format!("day_{:02}", day));
+    modules.sort();
+
+    let mut imports = "use crate::{\n".to_string();
+    let mut line = String::new();
+    for module in modules {
+        if !line.is_empty() && line.len() + module.len() + 2 > MAX_WIDTH {
+            imports.push_str(&line);
+            imports.push('\n');
+            line.clear();
+        }
+        if line.is_empty() {
+            line.push_str("   ");
+        }
+        line.push_str(&format!(" {},", module));
+    }
+    imports.push_str(&line);
+    imports.push('\n');
+    Ok(format!(
+        "{}{}{}",
+        &solution[..start],
+        imports,
+        &solution[end..]
+    ))
+}
+
+/// Add day `day` to the crate in `crate_dir`, with an empty input in
+/// `data_dir`. Returns the files written.
+pub fn new_day(crate_dir: &Path, data_dir: &Path, day: u8) -> Result<Vec<PathBuf>, String> {
+    let src = crate_dir.join("src");
+    let module_path = src.join(format!("day_{:02}.rs", day));
+    if module_path.exists() {
+        return Err(format!("{} already exists", module_path.display()));
+    }
+    let read = |path: &Path| {
+        std::fs::read_to_string(path)
+            .map_err(|e| format!("couldn't read {}: {}", path.display(), e))
+    };
+    let (lib_path, solution_path) = (src.join("lib.rs"), src.join("solution.rs"));
+    let lib = register_module(&read(&lib_path)?, day)?;
+    let solution = register_solution(&read(&solution_path)?, day)?;
+
+    let mut files = vec![
+        (module_path, module(day)),
+        (lib_path, lib),
+        (solution_path, solution),
+    ];
+    let input_path = data_dir.join(format!("day_{:02}.txt", day));
+    if !input_path.exists() {
+        files.push((input_path, String::new()));
+    }
+    for (path, contents) in files.iter() {
+        std::fs::write(path, contents)
+            .map_err(|e| format!("couldn't write {}: {}", path.display(), e))?;
+    }
+    Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_module() {
+        let lib = "pub mod solution;\n\npub mod day_01;\npub mod day_03;\n";
+        assert_eq!(
+            register_module(lib, 4).unwrap(),
+            "pub mod solution;\n\npub mod day_01;\npub mod day_03;\npub mod day_04;\n"
+        );
+        assert_eq!(
+            register_module(lib, 2).unwrap(),
+            "pub mod solution;\n\npub mod day_01;\npub mod day_02;\npub mod day_03;\n"
+        );
+        assert!(register_module(lib, 3).is_err());
+    }
+
+    #[test]
+    fn test_register_solution() {
+        let solution = "\
+use crate::{
+    day_01, day_02,
+};
+
+pub const DAYS: &[&dyn Day] = &[
+    &day_01::Day01,
+    &day_02::Day02,
+];
+";
+        assert_eq!(
+            register_solution(solution, 14).unwrap(),
+            "\
+use crate::{
+    day_01, day_02, day_14,
+};
+
+pub const DAYS: &[&dyn Day] = &[
+    &day_01::Day01,
+    &day_02::Day02,
+    &day_14::Day14,
+];
+"
+        );
+    }
+}
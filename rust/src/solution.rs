@@ -0,0 +1,139 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    day_01, day_02, day_03, day_04, day_05, day_06, day_07, day_08, day_09, day_10, day_11,
+    day_12, day_13,
+};
+
+/// The answer to one part of a puzzle, as it would be entered on the website.
+/// Some answers (e.g. rendered images) span several lines.
+pub type Answer = String;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    UnknownDay(u8),
+    UnknownPart(u8),
+    Parse(String),
+    NoAnswer(String),
+    /// The part hasn't been solved yet, as in a newly scaffolded day.
+    Unsolved,
+}
+
+impl Error {
+    /// Whether an error, as displayed, is `Error::Unsolved`.
+    pub fn is_unsolved(err: &str) -> bool {
+        err == Error::Unsolved.to_string()
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::UnknownDay(day) => write!(f, "day {} is not implemented", day),
+            Error::UnknownPart(part) => write!(f, "there is no part {}", part),
+            Error::Parse(msg) => write!(f, "couldn't parse input: {}", msg),
+            Error::NoAnswer(msg) => write!(f, "no answer: {}", msg),
+            Error::Unsolved => write!(f, "not solved yet"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::num::ParseIntError> for Error {
+    fn from(err: std::num::ParseIntError) -> Self {
+        Error::Parse(err.to_string())
+    }
+}
+
+/// A solution to one day's puzzle.
+pub trait Solution {
+    const DAY: u8;
+
+    type Input;
+
+    fn parse(data: &str) -> Result<Self::Input, Error>;
+
+    fn part_1(input: &Self::Input) -> Result<Answer, Error>;
+
+    fn part_2(input: &Self::Input) -> Result<Answer, Error>;
+}
+
+/// An answer along with how long it took to parse the input and solve.
+#[derive(Debug)]
+pub struct Run {
+    pub answer: Answer,
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+}
+
+impl Run {
+    pub fn total_time(&self) -> Duration {
+        self.parse_time + self.solve_time
+    }
+}
+
+/// Object-safe view of a `Solution`, so that every day can be kept in `DAYS`.
+pub trait Day {
+    fn day(&self) -> u8;
+
+    fn run(&self, part: u8, data: &str) -> Result<Run, Error>;
+
+    fn solve(&self, part: u8, data: &str) -> Result<Answer, Error> {
+        self.run(part, data).map(|run| run.answer)
+    }
+}
+
+impl<S> Day for S
+where
+    S: Solution,
+{
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn run(&self, part: u8, data: &str) -> Result<Run, Error> {
+        let start = Instant::now();
+        let input = S::parse(data)?;
+        let parse_time = start.elapsed();
+
+        let start = Instant::now();
+        let answer = match part {
+            1 => S::part_1(&input),
+            2 => S::part_2(&input),
+            _ => Err(Error::UnknownPart(part)),
+        }?;
+        let solve_time = start.elapsed();
+
+        Ok(Run {
+            answer,
+            parse_time,
+            solve_time,
+        })
+    }
+}
+
+pub const PARTS: &[u8] = &[1, 2];
+
+pub const DAYS: &[&dyn Day] = &[
+    &day_01::Day01,
+    &day_02::Day02,
+    &day_03::Day03,
+    &day_04::Day04,
+    &day_05::Day05,
+    &day_06::Day06,
+    &day_07::Day07,
+    &day_08::Day08,
+    &day_09::Day09,
+    &day_10::Day10,
+    &day_11::Day11,
+    &day_12::Day12,
+    &day_13::Day13,
+];
+
+pub fn find(day: u8) -> Result<&'static dyn Day, Error> {
+    DAYS.iter()
+        .find(|d| d.day() == day)
+        .copied()
+        .ok_or(Error::UnknownDay(day))
+}
@@ -0,0 +1,73 @@
+//! Run every day on its real input and compare with the recorded answers.
+
+extern crate aoc_2019;
+
+use aoc_2019::answers::{Answers, Outcome};
+use aoc_2019::input::{self, DataDirs};
+use aoc_2019::runner;
+use aoc_2019::solution::PARTS;
+
+const ANSWERS: &str = include_str!("../answers.txt");
+
+fn check_day(day: u8) {
+    let answers: Answers = ANSWERS.parse().unwrap();
+    let dirs = DataDirs::new(None);
+    let name = input::name(day, None);
+    for &part in PARTS {
+        let actual = runner::run_input(&dirs, day, part, &name).map(|run| run.answer);
+        let outcome = Outcome::new(answers.get(&name, part), actual);
+        assert_eq!(
+            outcome,
+            Outcome::Pass,
+            "{} part {}: {}",
+            name,
+            part,
+            outcome
+        );
+    }
+}
+
+macro_rules! days {
+    ($($test:ident: $day:expr,)*) => {
+        $(
+            #[test]
+            fn $test() {
+                check_day($day);
+            }
+        )*
+    };
+}
+
+days! {
+    day_01: 1,
+    day_02: 2,
+    day_03: 3,
+    day_04: 4,
+    day_05: 5,
+    day_06: 6,
+    day_07: 7,
+    day_08: 8,
+    day_09: 9,
+    day_10: 10,
+    day_11: 11,
+    day_12: 12,
+    day_13: 13,
+}
+
+/// Parts without an answer must not be solved yet, as in a newly scaffolded
+/// day; solved parts need an answer and a test above.
+#[test]
+fn every_day_has_answers() {
+    let answers: Answers = ANSWERS.parse().unwrap();
+    let dirs = DataDirs::new(None);
+    for solution in aoc_2019::solution::DAYS {
+        let name = input::name(solution.day(), None);
+        for &part in PARTS {
+            if answers.get(&name, part).is_none() {
+                let actual = runner::run_input(&dirs, solution.day(), part, &name);
+                let outcome = Outcome::new(None, actual.map(|run| run.answer));
+                assert_eq!(outcome, Outcome::Unsolved, "{} part {}", name, part);
+            }
+        }
+    }
+}
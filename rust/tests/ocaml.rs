@@ -0,0 +1,102 @@
+//! Compare answers with the OCaml solutions, when they have been built with
+//! `dune build` in `../ocaml`, or are in the directory named by
+//! `AOC_OCAML_BIN`. Without them, the check is skipped.
+
+extern crate aoc_2019;
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use aoc_2019::input::{self, DataDirs};
+use aoc_2019::runner;
+use aoc_2019::solution::PARTS;
+
+const OCAML_BIN_ENV: &str = "AOC_OCAML_BIN";
+
+const OCAML_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../ocaml");
+
+/// The days solved in OCaml, and whether the executable takes `--data`
+/// (day 4's input is built in).
+const OCAML_DAYS: &[(u8, bool)] = &[(1, true), (2, true), (3, true), (4, false)];
+
+fn bin_dirs() -> Vec<PathBuf> {
+    match std::env::var(OCAML_BIN_ENV) {
+        Ok(dir) => vec![PathBuf::from(dir)],
+        Err(_) => vec![
+            Path::new(OCAML_DIR).join("_build/default/bin"),
+            Path::new(OCAML_DIR).join("_build/install/default/bin"),
+        ],
+    }
+}
+
+/// The executable for `day`, built as `day_01.exe` or installed as `day-01`.
+fn executable(day: u8) -> Option<PathBuf> {
+    let names = [format!("day_{:02}.exe", day), format!("day-{:02}", day)];
+    bin_dirs()
+        .iter()
+        .flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+        .find(|path| path.is_file())
+}
+
+fn run_ocaml(exe: &Path, part: u8, data: Option<&Path>) -> Result<String, String> {
+    let mut cmd = Command::new(exe);
+    cmd.current_dir(OCAML_DIR)
+        .arg("--part")
+        .arg(part.to_string());
+    if let Some(data) = data {
+        cmd.arg("--data").arg(data);
+    }
+    let output = cmd
+        .output()
+        .map_err(|e| format!("couldn't run {}: {}", exe.display(), e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} failed: {}",
+            exe.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[test]
+fn ocaml_answers_agree() {
+    let dirs = DataDirs::new(None);
+    let mut checked = 0;
+    let mut disagreements = vec![];
+    for &(day, takes_data) in OCAML_DAYS {
+        let exe = match executable(day) {
+            Some(exe) => exe,
+            None => continue,
+        };
+        let name = input::name(day, None);
+        let data = match takes_data {
+            true => Some(dirs.find(&name).unwrap()),
+            false => None,
+        };
+        for &part in PARTS {
+            let rust = runner::run_input(&dirs, day, part, &name).map(|run| run.answer);
+            let ocaml = run_ocaml(&exe, part, data.as_deref());
+            checked += 1;
+            if rust != ocaml {
+                disagreements.push(format!(
+                    "day {} part {}: rust {:?}, ocaml {:?}",
+                    day, part, rust, ocaml
+                ));
+            }
+        }
+    }
+
+    if checked == 0 {
+        eprintln!(
+            "skipping: no OCaml executables in {:?}; build them with `dune build` or set {}",
+            bin_dirs(),
+            OCAML_BIN_ENV
+        );
+    }
+    assert!(
+        disagreements.is_empty(),
+        "the OCaml and Rust answers disagree:\n{}",
+        disagreements.join("\n")
+    );
+}
@@ -0,0 +1,94 @@
+//! Scaffold a new day in a copy of the crate, and check that the copy still
+//! builds, passes its answers tests, and that `check` and `all` succeed with
+//! the new day reported as unsolved.
+//!
+//! This builds a second copy of the crate offline, so it's slow and needs the
+//! dependencies already downloaded. It's ignored by default; run it with
+//! `cargo test --test scaffold -- --ignored`.
+
+extern crate aoc_2019;
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use aoc_2019::scaffold;
+
+const DAY: u8 = 14;
+
+fn copy_files(from: &Path, to: &Path, names: &[&str]) {
+    std::fs::create_dir_all(to).unwrap();
+    for name in names {
+        std::fs::copy(from.join(name), to.join(name)).unwrap();
+    }
+}
+
+fn copy_dir(from: &Path, to: &Path) {
+    let names: Vec<String> = std::fs::read_dir(from)
+        .unwrap()
+        .map(|entry| entry.unwrap())
+        .filter(|entry| entry.path().is_file())
+        .map(|entry| entry.file_name().into_string().unwrap())
+        .collect();
+    let names: Vec<&str> = names.iter().map(String::as_str).collect();
+    copy_files(from, to, &names);
+}
+
+/// Run cargo in `crate_dir`, building into a target directory kept between
+/// runs so that only the copied crate is rebuilt.
+fn cargo(crate_dir: &Path, args: &[&str]) -> Output {
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let output = Command::new(cargo)
+        .current_dir(crate_dir)
+        .args(args)
+        .env(
+            "CARGO_TARGET_DIR",
+            Path::new(env!("CARGO_TARGET_TMPDIR")).join("scaffold-target"),
+        )
+        .env_remove("AOC_DATA_DIR")
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "cargo {} failed:\n{}\n{}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    output
+}
+
+#[test]
+#[ignore = "builds a copy of the crate; run with --ignored"]
+fn scaffolded_day_keeps_the_tree_working() {
+    let source = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let root = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("scaffold");
+    if root.exists() {
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+    // Laid out like the repository, so the copy finds its data by default.
+    let (crate_dir, data_dir) = (root.join("rust"), root.join("ocaml/data"));
+    copy_files(
+        source,
+        &crate_dir,
+        &["Cargo.toml", "Cargo.lock", "answers.txt"],
+    );
+    copy_dir(&source.join("src"), &crate_dir.join("src"));
+    copy_files(
+        &source.join("tests"),
+        &crate_dir.join("tests"),
+        &["answers.rs"],
+    );
+    copy_dir(&source.join("../ocaml/data"), &data_dir);
+
+    scaffold::new_day(&crate_dir, &data_dir, DAY).unwrap();
+
+    cargo(&crate_dir, &["test", "--offline", "--quiet"]);
+    let check = cargo(&crate_dir, &["run", "--offline", "--quiet", "--", "check"]);
+    let check = String::from_utf8_lossy(&check.stdout);
+    for part in 1..=2 {
+        let line = format!("day_{} part {}: unsolved", DAY, part);
+        assert!(check.contains(&line), "{}", check);
+    }
+    assert!(check.contains("0 failed, 2 missing"), "{}", check);
+    cargo(&crate_dir, &["run", "--offline", "--quiet", "--", "all"]);
+}